@@ -0,0 +1,30 @@
+use crate::services::{CacheService, OutputService};
+use anyhow::Result;
+
+pub struct CacheCommand {
+    cache_service: Box<dyn CacheService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl CacheCommand {
+    pub fn new(
+        cache_service: Box<dyn CacheService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            cache_service,
+            output_service,
+        }
+    }
+
+    pub async fn clear(&self) -> Result<()> {
+        let removed = self.cache_service.clear()?;
+        self.output_service.display_success(&format!(
+            "Removed {} cached check result{} from {}",
+            removed,
+            if removed == 1 { "" } else { "s" },
+            self.cache_service.get_cache_dir().display()
+        ));
+        Ok(())
+    }
+}
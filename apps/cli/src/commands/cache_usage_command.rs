@@ -0,0 +1,75 @@
+use crate::models::{CacheUsage, CacheUsageReport, PackageManagerCheckResult};
+use crate::services::{CacheUsageService, OutputService, PackageManagerService};
+use anyhow::Result;
+use futures::future::join_all;
+
+pub struct CacheUsageCommand {
+    package_manager_service: Box<dyn PackageManagerService>,
+    cache_usage_service: Box<dyn CacheUsageService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl CacheUsageCommand {
+    pub fn new(
+        package_manager_service: Box<dyn PackageManagerService>,
+        cache_usage_service: Box<dyn CacheUsageService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            package_manager_service,
+            cache_usage_service,
+            output_service,
+        }
+    }
+
+    pub async fn execute(&self, prune: bool) -> Result<()> {
+        let managers: Vec<_> = self
+            .package_manager_service
+            .get_supported_managers()
+            .into_iter()
+            .filter(|pm| pm.cache_commands.is_some())
+            .collect();
+        let installed: Vec<_> = self
+            .package_manager_service
+            .check_managers(managers)
+            .await
+            .into_iter()
+            .filter(|r| r.success)
+            .collect();
+
+        if installed.is_empty() {
+            self.output_service
+                .display_info("No installed package manager with a cache was found");
+            return Ok(());
+        }
+
+        let usages = join_all(installed.iter().map(|r| self.get_usage(r, prune))).await;
+        self.output_service
+            .display_cache_usage(&CacheUsageReport { usages, pruned: prune });
+        Ok(())
+    }
+
+    async fn get_usage(&self, result: &PackageManagerCheckResult, prune: bool) -> CacheUsage {
+        let name = &result.package_manager.name;
+        let directory = match self.cache_usage_service.locate(result).await {
+            Ok(directory) => directory,
+            Err(e) => return CacheUsage::failed(name, None, e.to_string()),
+        };
+        let before = match self.cache_usage_service.measure(&directory) {
+            Ok(size) => size,
+            Err(e) => return CacheUsage::failed(name, Some(directory), e.to_string()),
+        };
+        if !prune {
+            return CacheUsage::new(name, directory, before);
+        }
+
+        // A failed cleanup may still have removed part of the cache.
+        let pruned = self.cache_usage_service.prune(result).await;
+        let after = self.cache_usage_service.measure(&directory).unwrap_or(before);
+        let usage = CacheUsage::new(name, directory, after).with_freed(before.saturating_sub(after));
+        match pruned {
+            Ok(()) => usage,
+            Err(e) => usage.with_error(e.to_string()),
+        }
+    }
+}
@@ -1,43 +1,413 @@
-use crate::services::{PackageManagerService, OutputService};
+use crate::models::{
+    get_registry_package, Ecosystem, ManagerKind, NodeVersionCheck, OutdatedCheck, PackageManagerCheckResult, PinCheck, Requirement, RequirementStatus, YarnDetails, YarnFlavor,
+};
+use crate::services::{
+    ManifestService, NodeVersionService, OutputService, PackageManagerService, RegistryService, YarnService,
+};
+use crate::utils::constants::*;
 use anyhow::Result;
+use futures::future::join_all;
+
+#[derive(Debug, Clone, Default)]
+pub struct CheckOptions {
+    pub detailed: bool,
+    pub only: Option<String>,
+    pub require: Option<String>,
+    pub all_paths: bool,
+    pub ecosystem: Option<String>,
+    pub outdated: bool,
+}
 
 pub struct CheckCommand {
     package_manager_service: Box<dyn PackageManagerService>,
+    manifest_service: Box<dyn ManifestService>,
+    yarn_service: Box<dyn YarnService>,
+    registry_service: Box<dyn RegistryService>,
+    node_version_service: Box<dyn NodeVersionService>,
     output_service: Box<dyn OutputService>,
+    ecosystems: Vec<Ecosystem>,
 }
 
 impl CheckCommand {
     pub fn new(
         package_manager_service: Box<dyn PackageManagerService>,
+        manifest_service: Box<dyn ManifestService>,
+        yarn_service: Box<dyn YarnService>,
+        registry_service: Box<dyn RegistryService>,
+        node_version_service: Box<dyn NodeVersionService>,
         output_service: Box<dyn OutputService>,
+        ecosystems: Vec<Ecosystem>,
     ) -> Self {
         Self {
             package_manager_service,
+            manifest_service,
+            yarn_service,
+            registry_service,
+            node_version_service,
             output_service,
+            ecosystems,
         }
     }
 
-    pub async fn execute(&self, detailed: bool, only: Option<&str>) -> Result<()> {
+    pub async fn execute(&self, options: &CheckOptions) -> Result<u8> {
         let all_managers = self.package_manager_service.get_supported_managers();
-        
-        let managers_to_check = if let Some(only_list) = only {
-            let selected: Vec<&str> = only_list.split(',').map(|s| s.trim()).collect();
-            all_managers
-                .into_iter()
-                .filter(|pm| selected.contains(&pm.name.as_str()))
-                .collect()
-        } else {
-            all_managers
+
+        let requirements = match options.require.as_deref().map(Requirement::parse_list) {
+            Some(Ok(requirements)) => requirements,
+            Some(Err(e)) => {
+                self.output_service.display_error(&e.to_string());
+                return Ok(EXIT_ERROR);
+            }
+            None => Vec::new(),
+        };
+
+        let ecosystems = match options.ecosystem.as_deref().map(Ecosystem::parse_list) {
+            Some(Ok(ecosystems)) => ecosystems,
+            Some(Err(e)) => {
+                self.output_service.display_error(&e.to_string());
+                return Ok(EXIT_ERROR);
+            }
+            None => self.ecosystems.clone(),
+        };
+
+        if let Some(unknown) = requirements
+            .iter()
+            .find(|r| !all_managers.iter().any(|pm| pm.name == r.name))
+        {
+            self.output_service.display_error(&format!(
+                "Unknown package manager '{}' in requirement '{}'",
+                unknown.name, unknown.expression
+            ));
+            return Ok(EXIT_ERROR);
+        }
+
+        // A broken package.json should not stop the managers from being checked.
+        let package_json = match self.manifest_service.find_package_json(&std::env::current_dir()?) {
+            Ok(package_json) => package_json,
+            Err(e) => {
+                self.output_service.display_error(&e.to_string());
+                None
+            }
         };
+        let pin = package_json
+            .as_ref()
+            .and_then(|p| p.get_pin().map(|pin| (pin, p.path.clone())));
+
+        if let Some((pin, _)) = &pin {
+            if !all_managers.iter().any(|pm| pm.name == pin.name) {
+                self.output_service.display_info(&format!(
+                    "packageManager '{}' is not a supported package manager",
+                    pin.raw
+                ));
+            }
+        }
+
+        let mut engines = Vec::new();
+        if let Some(package_json) = &package_json {
+            let mut names: Vec<_> = package_json.engines.keys().collect();
+            names.sort();
+            for name in names.into_iter().filter(|n| all_managers.iter().any(|pm| &pm.name == *n)) {
+                match Requirement::from_range(name, &package_json.engines[name]) {
+                    Ok(requirement) => engines.push(requirement),
+                    Err(e) => self.output_service.display_error(&format!(
+                        "Invalid engines.{} range in {}: {}",
+                        name,
+                        package_json.path.display(),
+                        e
+                    )),
+                }
+            }
+        }
+
+        let node_pins = match self.node_version_service.find_pins(&std::env::current_dir()?) {
+            Ok(node_pins) => node_pins,
+            Err(e) => {
+                self.output_service.display_error(&format!("{:#}", e));
+                Vec::new()
+            }
+        };
+
+        // Required and pinned managers are always checked, even when `--only`
+        // or the enabled ecosystems leave them out, and so is node when a
+        // Node version file pins it. `--only` may name a manager from any
+        // ecosystem.
+        let selected: Option<Vec<&str>> = options
+            .only
+            .as_deref()
+            .map(|only_list| only_list.split(',').map(|s| s.trim()).collect());
+        let managers_to_check: Vec<_> = all_managers
+            .into_iter()
+            .filter(|pm| {
+                let is_selected = match &selected {
+                    Some(selected) => selected.contains(&pm.name.as_str()),
+                    None => ecosystems.contains(&pm.ecosystem),
+                };
+                is_selected
+                    || requirements.iter().any(|r| r.name == pm.name)
+                    || pin.as_ref().is_some_and(|(pin, _)| pin.name == pm.name)
+                    || (pm.name == "node" && !node_pins.is_empty())
+            })
+            .collect();
 
         if managers_to_check.is_empty() {
             self.output_service.display_error("No valid package managers specified");
-            return Ok(());
+            return Ok(EXIT_ERROR);
+        }
+
+        let results = self
+            .package_manager_service
+            .check_managers(managers_to_check)
+            .await;
+        let results = self.apply_yarn_project(results).await;
+        let version_managers: Vec<String> = results
+            .iter()
+            .filter(|r| r.package_manager.kind == ManagerKind::VersionManager && r.package_manager.is_installed)
+            .map(|r| r.package_manager.name.clone())
+            .collect();
+        let results: Vec<_> = results
+            .into_iter()
+            .map(|mut result| {
+                if let Some(requirement) = requirements
+                    .iter()
+                    .find(|r| r.name == result.package_manager.name)
+                {
+                    let evaluation = requirement.evaluate(&result);
+                    result = result.with_requirement(evaluation);
+                }
+                if let Some(engine) = engines
+                    .iter()
+                    .find(|r| r.name == result.package_manager.name)
+                {
+                    let evaluation = engine.evaluate(&result);
+                    result = result.with_engine(evaluation);
+                }
+                if let Some((pin, source)) = pin
+                    .as_ref()
+                    .filter(|(pin, _)| pin.name == result.package_manager.name)
+                {
+                    let installed = result.package_manager.parsed_version.as_ref();
+                    let check = PinCheck {
+                        pinned: pin.raw.clone(),
+                        installed: installed.map(|v| v.to_string()),
+                        source: source.clone(),
+                        matches: pin.matches(installed),
+                    };
+                    result = result.with_pin(check);
+                }
+                if result.package_manager.name == "node" && !node_pins.is_empty() {
+                    let installed = result.package_manager.parsed_version.as_ref();
+                    let check = NodeVersionCheck::new(node_pins.clone(), installed, &version_managers);
+                    result = result.with_node_version(check);
+                }
+                result
+            })
+            .collect();
+
+        let results = if options.all_paths {
+            self.attach_installations(results).await
+        } else {
+            results
+        };
+
+        let results = if options.outdated {
+            self.attach_outdated(results).await?
+        } else {
+            results
+        };
+
+        self.output_service.display_package_manager_results(&results, options.detailed);
+
+        if let Some(corepack) = results.iter().find(|r| r.package_manager.name == "corepack") {
+            let shims = self.package_manager_service.detect_corepack_shims();
+            self.output_service.display_corepack_status(corepack, &shims);
         }
 
-        let results = self.package_manager_service.check_managers(managers_to_check).await;
-        self.output_service.display_package_manager_results(&results, detailed);
+        Ok(Self::exit_code(&results))
+    }
+
+    /// Replaces the global yarn with the project's `yarnPath` release when
+    /// there is one, and records which Yarn flavor and linker are in effect.
+    async fn apply_yarn_project(
+        &self,
+        results: Vec<PackageManagerCheckResult>,
+    ) -> Vec<PackageManagerCheckResult> {
+        let yarnrc = match self.yarn_service.find_yarnrc(&std::env::current_dir().unwrap_or_default()) {
+            Ok(yarnrc) => yarnrc,
+            Err(e) => {
+                self.output_service.display_error(&e.to_string());
+                None
+            }
+        };
+
+        let mut updated = Vec::with_capacity(results.len());
+        for result in results {
+            if result.package_manager.name != "yarn" {
+                updated.push(result);
+                continue;
+            }
+
+            let global = result.clone();
+            let yarn_path = yarnrc
+                .as_ref()
+                .and_then(|rc| rc.get_yarn_path())
+                .filter(|path| path.is_file());
+
+            let effective = match &yarn_path {
+                Some(path) => {
+                    let mut manager = global.package_manager.clone();
+                    manager.version = None;
+                    manager.parsed_version = None;
+                    self.package_manager_service.check_binary(&manager, path).await
+                }
+                None => global.clone(),
+            };
+
+            // Nothing to report for a yarn that is neither installed nor configured.
+            if !effective.package_manager.is_installed && yarnrc.is_none() {
+                updated.push(effective);
+                continue;
+            }
+
+            let flavor = effective
+                .package_manager
+                .parsed_version
+                .as_ref()
+                .map(|v| YarnFlavor::from_major(v.major))
+                .or(yarnrc.as_ref().map(|_| YarnFlavor::Berry));
+            let linker = yarnrc
+                .as_ref()
+                .and_then(|rc| rc.node_linker.clone())
+                .or_else(|| flavor.map(|f| f.get_default_linker().to_string()));
+            let releases = yarnrc
+                .as_ref()
+                .and_then(|rc| rc.path.parent())
+                .map(|dir| self.yarn_service.list_releases(dir))
+                .unwrap_or_default();
+
+            let details = YarnDetails {
+                flavor,
+                linker,
+                yarnrc: yarnrc.as_ref().map(|rc| rc.path.clone()),
+                yarn_path: yarn_path.clone(),
+                releases,
+                global_version: yarn_path.as_ref().and(global.package_manager.version.clone()),
+                global_path: yarn_path.as_ref().and(global.package_manager.path.clone()),
+            };
+
+            updated.push(effective.with_yarn(details));
+        }
+
+        updated
+    }
+
+    async fn attach_installations(
+        &self,
+        results: Vec<PackageManagerCheckResult>,
+    ) -> Vec<PackageManagerCheckResult> {
+        let lookups = results
+            .iter()
+            .map(|r| self.package_manager_service.find_installations(&r.package_manager));
+        let installations = join_all(lookups).await;
 
-        Ok(())
+        results
+            .into_iter()
+            .zip(installations)
+            .map(|(result, installations)| result.with_installations(installations))
+            .collect()
     }
-} 
\ No newline at end of file
+
+    /// Looks up the latest release of each installed manager that is
+    /// published to the npm registry. Lookups that fail are reported on the
+    /// row instead of failing the check.
+    async fn attach_outdated(
+        &self,
+        results: Vec<PackageManagerCheckResult>,
+    ) -> Result<Vec<PackageManagerCheckResult>> {
+        // Without a registry every lookup fails the same way; that is
+        // reported per row like any other failed lookup.
+        let registry = match self.registry_service.resolve_registry(&std::env::current_dir()?) {
+            Ok(registry) => registry,
+            Err(e) => {
+                let message = format!("{:#}", e);
+                return Ok(results
+                    .into_iter()
+                    .map(|result| {
+                        if get_registry_package(&result).is_some() && result.package_manager.parsed_version.is_some() {
+                            let outdated = OutdatedCheck::failed(&result, "", message.clone());
+                            result.with_outdated(outdated)
+                        } else {
+                            result
+                        }
+                    })
+                    .collect());
+            }
+        };
+
+        let lookups = results.iter().map(|result| {
+            let package = get_registry_package(result)
+                .filter(|_| result.package_manager.parsed_version.is_some());
+            let registry = &registry;
+            async move {
+                match package {
+                    Some(package) => Some(self.registry_service.get_latest_version(registry, package).await),
+                    None => None,
+                }
+            }
+        });
+        let latest = join_all(lookups).await;
+
+        Ok(results
+            .into_iter()
+            .zip(latest)
+            .map(|(result, latest)| match latest {
+                Some(Ok(version)) => {
+                    let outdated = OutdatedCheck::new(&result, &registry.url, version);
+                    result.with_outdated(outdated)
+                }
+                Some(Err(e)) => {
+                    let message = format!("{}: {}", e, e.root_cause());
+                    let outdated = OutdatedCheck::failed(&result, &registry.url, message);
+                    result.with_outdated(outdated)
+                }
+                None => result,
+            })
+            .collect())
+    }
+
+    fn exit_code(results: &[PackageManagerCheckResult]) -> u8 {
+        let mut missing = false;
+        let mut unsatisfied = false;
+
+        for result in results {
+            if let Some(requirement) = &result.requirement {
+                match requirement.status {
+                    RequirementStatus::Missing | RequirementStatus::UnknownVersion => missing = true,
+                    RequirementStatus::Unsatisfied => unsatisfied = true,
+                    RequirementStatus::Satisfied => {}
+                }
+            }
+            // Engines only constrain managers that are actually used, so a
+            // missing one is not a failure.
+            if let Some(engine) = &result.engine {
+                if engine.status == RequirementStatus::Unsatisfied {
+                    unsatisfied = true;
+                }
+            }
+            if let Some(pin) = &result.pin {
+                if !result.package_manager.is_installed {
+                    missing = true;
+                } else if !pin.matches {
+                    unsatisfied = true;
+                }
+            }
+        }
+
+        if missing {
+            EXIT_REQUIREMENT_MISSING
+        } else if unsatisfied {
+            EXIT_REQUIREMENT_UNSATISFIED
+        } else {
+            EXIT_SUCCESS
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::services::{ProjectService, OutputService};
 use crate::models::ProjectConfig;
 use anyhow::Result;
+use crate::utils::constants::DEFAULT_TEMPLATE;
 
 pub struct CreateCommand {
     project_service: Box<dyn ProjectService>,
@@ -19,7 +20,7 @@ impl CreateCommand {
     }
 
     pub async fn execute(&self, name: &str, template: Option<&str>) -> Result<()> {
-        let template_name = template.unwrap_or("fullstack-nextjs");
+        let template_name = template.unwrap_or(DEFAULT_TEMPLATE);
         
         // Validate template exists
         if self.project_service.get_template(template_name).is_none() {
@@ -37,7 +38,8 @@ impl CreateCommand {
 
     pub async fn list_templates(&self) -> Result<()> {
         let templates = self.project_service.list_templates();
-        self.output_service.display_project_templates(&templates);
+        let warnings = self.project_service.get_template_warnings();
+        self.output_service.display_project_templates(&templates, &warnings);
         Ok(())
     }
 } 
\ No newline at end of file
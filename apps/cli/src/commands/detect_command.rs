@@ -0,0 +1,60 @@
+use crate::services::{DetectionService, OutputService, PackageManagerService};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct DetectCommand {
+    detection_service: Box<dyn DetectionService>,
+    package_manager_service: Box<dyn PackageManagerService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl DetectCommand {
+    pub fn new(
+        detection_service: Box<dyn DetectionService>,
+        package_manager_service: Box<dyn PackageManagerService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            detection_service,
+            package_manager_service,
+            output_service,
+        }
+    }
+
+    pub async fn execute(&self, directory: Option<&Path>) -> Result<()> {
+        let directory = match directory {
+            Some(directory) => directory.to_path_buf(),
+            None => std::env::current_dir()?,
+        };
+
+        let mut detection = self.detection_service.detect(&directory)?;
+
+        let installed = match detection.manager.as_deref() {
+            Some(name) => {
+                let manager = self
+                    .package_manager_service
+                    .get_supported_managers()
+                    .into_iter()
+                    .find(|pm| pm.name == name);
+                match manager {
+                    Some(manager) => Some(self.package_manager_service.check_manager(&manager).await?),
+                    None => {
+                        detection.add_warning(format!("{} is not a supported package manager", name));
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        if let Some(result) = installed.as_ref().filter(|r| !r.package_manager.is_installed) {
+            detection.add_warning(format!(
+                "{} is used by this project but is not installed",
+                result.package_manager.name
+            ));
+        }
+
+        self.output_service.display_detection(&detection, installed.as_ref());
+        Ok(())
+    }
+}
@@ -0,0 +1,513 @@
+use crate::models::{
+    redact_url, DiagnosticCategory, DoctorReport, Ecosystem, Finding, PackageJson, PackageManagerCheckResult, ProjectDetection,
+    Requirement, RequirementStatus, Severity, CheckStatus,
+};
+use crate::services::{
+    ConfigService, DetectionService, ManifestService, OutputService, PackageManagerService, RegistryService,
+};
+use crate::utils::constants::*;
+use anyhow::Result;
+use futures::future::join_all;
+use std::path::Path;
+
+pub struct DoctorCommand {
+    package_manager_service: Box<dyn PackageManagerService>,
+    config_service: Box<dyn ConfigService>,
+    manifest_service: Box<dyn ManifestService>,
+    detection_service: Box<dyn DetectionService>,
+    registry_service: Box<dyn RegistryService>,
+    output_service: Box<dyn OutputService>,
+    ecosystems: Vec<Ecosystem>,
+}
+
+impl DoctorCommand {
+    pub fn new(
+        package_manager_service: Box<dyn PackageManagerService>,
+        config_service: Box<dyn ConfigService>,
+        manifest_service: Box<dyn ManifestService>,
+        detection_service: Box<dyn DetectionService>,
+        registry_service: Box<dyn RegistryService>,
+        output_service: Box<dyn OutputService>,
+        ecosystems: Vec<Ecosystem>,
+    ) -> Self {
+        Self {
+            package_manager_service,
+            config_service,
+            manifest_service,
+            detection_service,
+            registry_service,
+            output_service,
+            ecosystems,
+        }
+    }
+
+    /// Runs every diagnostic. Exits with `EXIT_ERROR` when any finding is an
+    /// error; warnings alone do not fail.
+    pub async fn execute(&self, json: bool) -> Result<u8> {
+        let directory = std::env::current_dir()?;
+        let mut report = DoctorReport::new(directory.clone());
+
+        // Node is diagnosed even when the JavaScript ecosystem is not enabled.
+        let managers: Vec<_> = self
+            .package_manager_service
+            .get_supported_managers()
+            .into_iter()
+            .filter(|pm| self.ecosystems.contains(&pm.ecosystem) || pm.name == "node")
+            .collect();
+        let results = self.package_manager_service.check_managers(managers).await;
+        let package_json = self.manifest_service.find_package_json(&directory);
+        let detection = self.detection_service.detect(&directory).ok();
+
+        report.findings.extend(self.diagnose_config(&directory, &package_json));
+        let package_json = package_json.ok().flatten();
+        report.findings.extend(self.diagnose_runtime(&results, package_json.as_ref()));
+        report.findings.extend(self.diagnose_managers(&results, detection.as_ref()));
+        report.findings.extend(self.diagnose_path(&results).await);
+        report.findings.extend(self.diagnose_lockfiles(detection.as_ref(), package_json.is_some()));
+        report.findings.extend(self.diagnose_registry(&directory).await);
+
+        if json {
+            self.output_service.display_json(&serde_json::to_string_pretty(&report)?);
+        } else {
+            self.output_service.display_doctor_report(&report);
+        }
+
+        Ok(if report.get_worst_severity() == Severity::Error {
+            EXIT_ERROR
+        } else {
+            EXIT_SUCCESS
+        })
+    }
+
+    fn diagnose_config(&self, directory: &Path, package_json: &Result<Option<PackageJson>>) -> Vec<Finding> {
+        let category = DiagnosticCategory::Config;
+        let mut findings = Vec::new();
+
+        let config_path = self.config_service.get_config_path();
+        match self.config_service.load_config() {
+            Ok(config) if !config.warnings.is_empty() => {
+                for warning in config.warnings {
+                    findings.push(
+                        Finding::new(category, Severity::Warning, format!("{}: {}", config_path, warning))
+                            .with_fix(format!("Fix that entry in {}; the rest of the file is used", config_path)),
+                    );
+                }
+            }
+            Ok(_) if Path::new(&config_path).exists() => {
+                findings.push(Finding::ok(category, format!("{} is valid", config_path)))
+            }
+            Ok(_) => findings.push(
+                Finding::new(category, Severity::Info, format!("No {} here, defaults are used", config_path))
+                    .with_fix("Run `fazrepo init` to create one".to_string()),
+            ),
+            Err(e) => findings.push(
+                Finding::new(category, Severity::Error, format!("{} is invalid and ignored: {:#}", config_path, e))
+                    .with_fix(format!("Fix the JSON in {} or delete it and run `fazrepo init`", config_path)),
+            ),
+        }
+
+        match package_json {
+            Ok(Some(package_json)) => findings.push(Finding::ok(
+                category,
+                format!("{} is valid", package_json.path.display()),
+            )),
+            Ok(None) => {}
+            Err(e) => findings.push(
+                Finding::new(category, Severity::Error, format!("{:#}", e))
+                    .with_fix("Fix the JSON syntax in package.json; npm, yarn and pnpm cannot read it either".to_string()),
+            ),
+        }
+
+        if let Err(e) = self.registry_service.inspect(directory) {
+            findings.push(
+                Finding::new(category, Severity::Error, format!("{:#}", e))
+                    .with_fix("Fix the syntax of that file; run `fazrepo registry` to see every file that is read".to_string()),
+            );
+        }
+
+        findings
+    }
+
+    fn diagnose_runtime(&self, results: &[PackageManagerCheckResult], package_json: Option<&PackageJson>) -> Vec<Finding> {
+        let category = DiagnosticCategory::Runtime;
+        let Some(node) = results.iter().find(|r| r.package_manager.name == "node") else {
+            return Vec::new();
+        };
+        let pm = &node.package_manager;
+
+        match node.status {
+            CheckStatus::Ok => {}
+            CheckStatus::NotInstalled => {
+                // Only a JavaScript project needs Node.js.
+                let severity = if package_json.is_some() { Severity::Error } else { Severity::Warning };
+                return vec![Finding::new(category, severity, "Node.js is not installed".to_string())
+                    .with_fix("Install the current LTS from https://nodejs.org or with a version manager, e.g. `nvm install --lts`".to_string())];
+            }
+            CheckStatus::VersionCheckFailed | CheckStatus::TimedOut => {
+                return vec![Finding::new(
+                    category,
+                    Severity::Error,
+                    format!(
+                        "node at {} does not run: {}",
+                        pm.get_path_display(),
+                        node.error_message.as_deref().unwrap_or("unknown error")
+                    ),
+                )
+                .with_fix("Reinstall Node.js, or switch to a working version with your version manager".to_string())];
+            }
+        }
+
+        let version = pm
+            .parsed_version
+            .as_ref()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| pm.get_version_display());
+        let mut findings = Vec::new();
+        match pm.parsed_version.as_ref() {
+            Some(version) if version.major < MIN_MAINTAINED_NODE_MAJOR => findings.push(
+                Finding::new(category, Severity::Warning, format!("Node.js {} is end-of-life", version))
+                    .with_fix(format!("Upgrade to Node.js {} or newer, e.g. `nvm install --lts`", MIN_MAINTAINED_NODE_MAJOR)),
+            ),
+            _ => findings.push(Finding::ok(
+                category,
+                format!("Node.js {} ({})", version, pm.get_path_display()),
+            )),
+        }
+
+        let range = package_json.and_then(|p| p.engines.get("node"));
+        let requirement = range.and_then(|range| Some((range, Requirement::from_range("node", range).ok()?)));
+        if let Some((range, requirement)) = requirement {
+            let evaluation = requirement.evaluate(node);
+            if evaluation.status == RequirementStatus::Unsatisfied {
+                findings.push(
+                    Finding::new(
+                        category,
+                        Severity::Error,
+                        format!(
+                            "Node.js {} does not satisfy engines.node `{}`",
+                            version,
+                            range
+                        ),
+                    )
+                    .with_fix("Install a matching Node.js version and switch to it, e.g. with `nvm use`, `fnm use` or `volta pin node`".to_string()),
+                );
+            } else {
+                findings.push(Finding::ok(category, format!("engines.node `{}` is satisfied", range)));
+            }
+        }
+
+        findings
+    }
+
+    fn diagnose_managers(&self, results: &[PackageManagerCheckResult], detection: Option<&ProjectDetection>) -> Vec<Finding> {
+        let category = DiagnosticCategory::Managers;
+        let mut findings = Vec::new();
+        let project_manager = detection.and_then(|d| d.manager.as_deref());
+
+        for result in results.iter().filter(|r| r.package_manager.name != "node") {
+            let pm = &result.package_manager;
+            let error = result.error_message.as_deref().unwrap_or("unknown error");
+            match result.status {
+                CheckStatus::Ok => findings.push(Finding::ok(
+                    category,
+                    format!("{} {} ({})", pm.name, pm.get_version_display(), pm.get_path_display()),
+                )),
+                CheckStatus::TimedOut => findings.push(
+                    Finding::new(category, Severity::Warning, format!("{}: {}", pm.name, error)).with_fix(format!(
+                        "Run `{} {}` to see whether it waits for input (Corepack asks before downloading a manager), or raise the limit with `--timeout`",
+                        pm.command,
+                        pm.version_args.join(" ")
+                    )),
+                ),
+                CheckStatus::VersionCheckFailed => findings.push(
+                    Finding::new(
+                        category,
+                        Severity::Error,
+                        format!("{} at {} is installed but does not run: {}", pm.name, pm.get_path_display(), error),
+                    )
+                    .with_fix(format!("Reinstall {}, or make sure the runtime it needs is on PATH", pm.name)),
+                ),
+                CheckStatus::NotInstalled if project_manager == Some(pm.name.as_str()) => findings.push(
+                    Finding::new(
+                        category,
+                        Severity::Error,
+                        format!("This project uses {} but it is not installed", pm.name),
+                    )
+                    .with_fix(get_install_hint(&pm.name)),
+                ),
+                CheckStatus::NotInstalled => {}
+            }
+
+            let pin = detection.and_then(|d| d.pin.as_ref()).filter(|pin| pin.name == pm.name);
+            if let Some(pin) = pin.filter(|_| result.success) {
+                if !pin.matches(pm.parsed_version.as_ref()) {
+                    findings.push(
+                        Finding::new(
+                            category,
+                            Severity::Warning,
+                            format!("packageManager pins {} but {} {} is installed", pin.raw, pm.name, pm.get_version_display()),
+                        )
+                        .with_fix(format!("Run `corepack enable` so Corepack provides {}, or install that version", pin.raw)),
+                    );
+                }
+            }
+        }
+
+        findings
+    }
+
+    async fn diagnose_path(&self, results: &[PackageManagerCheckResult]) -> Vec<Finding> {
+        let category = DiagnosticCategory::Path;
+        let installed: Vec<_> = results.iter().filter(|r| r.success).collect();
+        let lookups = installed
+            .iter()
+            .map(|r| self.package_manager_service.find_installations(&r.package_manager));
+        let installations = join_all(lookups).await;
+
+        let mut findings = Vec::new();
+        for (result, installations) in installed.into_iter().zip(installations) {
+            let result = result.clone().with_installations(installations);
+            let Some(active) = result.installations.iter().find(|i| i.is_active) else {
+                continue;
+            };
+            for shadowed in result.get_shadowed_conflicts() {
+                findings.push(
+                    Finding::new(
+                        category,
+                        Severity::Warning,
+                        format!(
+                            "{} {} at {} is shadowed by {} at {}",
+                            result.package_manager.name,
+                            shadowed.version.as_deref().unwrap_or("unknown"),
+                            shadowed.path.display(),
+                            active.version.as_deref().unwrap_or("unknown"),
+                            active.path.display()
+                        ),
+                    )
+                    .with_fix(format!(
+                        "Remove the stale installation at {}, or reorder PATH so the one you want comes first",
+                        shadowed.path.display()
+                    )),
+                );
+            }
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::ok(category, "No shadowed installations with a different version".to_string()));
+        }
+        findings
+    }
+
+    fn diagnose_lockfiles(&self, detection: Option<&ProjectDetection>, has_package_json: bool) -> Vec<Finding> {
+        let category = DiagnosticCategory::Lockfiles;
+        let Some(detection) = detection.filter(|_| has_package_json) else {
+            return Vec::new();
+        };
+
+        let managers = detection.get_lockfile_managers();
+        let files: Vec<String> = detection
+            .lockfiles
+            .iter()
+            .map(|l| format!("{} ({})", l.path.file_name().unwrap_or_default().to_string_lossy(), l.manager))
+            .collect();
+        let mut findings = Vec::new();
+
+        if managers.len() > 1 {
+            let fix = match &detection.pin {
+                Some(pin) if managers.contains(&pin.name.as_str()) => {
+                    format!("Keep the {} lockfile and delete the others", pin.name)
+                }
+                Some(pin) => format!("Run `{} install` and delete the other lockfiles", pin.name),
+                None => "Choose one package manager, delete the other lockfiles and pin it with the packageManager field".to_string(),
+            };
+            findings.push(
+                Finding::new(category, Severity::Warning, format!("Conflicting lockfiles: {}", files.join(", ")))
+                    .with_fix(fix),
+            );
+        }
+
+        if let Some(pin) = detection.pin.as_ref().filter(|pin| !managers.is_empty() && !managers.contains(&pin.name.as_str())) {
+            findings.push(
+                Finding::new(
+                    category,
+                    Severity::Warning,
+                    format!("packageManager pins {} but the lockfiles belong to {}", pin.name, managers.join(", ")),
+                )
+                .with_fix(format!("Run `{} install` and delete the other lockfiles, or change packageManager", pin.name)),
+            );
+        }
+
+        if managers.is_empty() {
+            let manager = detection.manager.as_deref().unwrap_or("npm");
+            findings.push(
+                Finding::new(category, Severity::Info, "No lockfile found".to_string())
+                    .with_fix(format!("Run `{} install` and commit the lockfile so installs are reproducible", manager)),
+            );
+        } else if findings.is_empty() {
+            findings.push(Finding::ok(category, files.join(", ")));
+        }
+
+        findings
+    }
+
+    async fn diagnose_registry(&self, directory: &Path) -> Vec<Finding> {
+        let category = DiagnosticCategory::Registry;
+        let registry = match self.registry_service.resolve_registry(directory) {
+            Ok(registry) => registry,
+            Err(e) => return vec![Finding::new(category, Severity::Error, format!("{:#}", e))],
+        };
+        let source = registry.get_source_display();
+        let url = redact_url(&registry.url);
+
+        let finding = match self.registry_service.ping(&registry).await {
+            Ok(status) if (200..300).contains(&status) => {
+                Finding::ok(category, format!("{} is reachable (from {})", url, source))
+            }
+            Ok(status @ (401 | 403)) => Finding::new(
+                category,
+                Severity::Warning,
+                format!("{} rejected the request with HTTP {} (from {})", url, status, source),
+            )
+            .with_fix("Check the auth token for this registry; `fazrepo registry` shows which file sets it".to_string()),
+            Ok(status) => Finding::new(
+                category,
+                Severity::Warning,
+                format!("{} answered npm ping with HTTP {} (from {})", url, status, source),
+            )
+            .with_fix("Make sure the URL points at the registry root, e.g. https://registry.npmjs.org/".to_string()),
+            Err(e) => Finding::new(
+                category,
+                Severity::Error,
+                format!("Cannot reach {} (from {}): {}", url, source, e.root_cause()),
+            )
+            .with_fix("Check the network and proxy settings (HTTPS_PROXY, https-proxy in .npmrc), or use a reachable mirror with --registry or settings.npm_registry".to_string()),
+        };
+
+        vec![finding]
+    }
+}
+
+fn get_install_hint(manager: &str) -> String {
+    match manager {
+        "npm" => "npm ships with Node.js; reinstall Node.js from https://nodejs.org".to_string(),
+        "yarn" | "pnpm" => format!("Run `corepack enable` (bundled with Node.js) or `npm install -g {}`", manager),
+        "bun" => "Install it with `curl -fsSL https://bun.sh/install | bash` or `npm install -g bun`".to_string(),
+        _ => format!("Install {} and make sure it is on PATH", manager),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Lockfile, PackageManager, PackageManagerPin, RegistrySource};
+    use crate::services::{
+        ColoredOutputService, DefaultConfigService, DefaultDetectionService, DefaultManifestService,
+        DefaultPackageManagerService, DefaultRegistryService,
+    };
+    use semver::Version;
+    use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn doctor(registry_service: DefaultRegistryService) -> DoctorCommand {
+        DoctorCommand::new(
+            Box::new(DefaultPackageManagerService::new()),
+            Box::new(DefaultConfigService::new()),
+            Box::new(DefaultManifestService::new()),
+            Box::new(DefaultDetectionService::new()),
+            Box::new(registry_service),
+            Box::new(ColoredOutputService::new()),
+            vec![Ecosystem::JavaScript],
+        )
+    }
+
+    fn installed(name: &str, version: &str) -> PackageManagerCheckResult {
+        PackageManagerCheckResult::success(
+            PackageManager::new(name, name, "", name)
+                .with_parsed_version(Version::parse(version).unwrap())
+                .mark_installed(),
+        )
+    }
+
+    fn missing(name: &str) -> PackageManagerCheckResult {
+        PackageManagerCheckResult::failure(PackageManager::new(name, name, "", name), "not found".to_string())
+    }
+
+    fn severities(findings: &[Finding]) -> Vec<Severity> {
+        findings.iter().map(|f| f.severity).collect()
+    }
+
+    #[test]
+    fn test_missing_node_is_an_error_only_in_a_javascript_project() {
+        let doctor = doctor(DefaultRegistryService::new());
+        let package_json = PackageJson::default();
+
+        assert_eq!(severities(&doctor.diagnose_runtime(&[missing("node")], None)), vec![Severity::Warning]);
+        assert_eq!(
+            severities(&doctor.diagnose_runtime(&[missing("node")], Some(&package_json))),
+            vec![Severity::Error]
+        );
+    }
+
+    #[test]
+    fn test_diagnose_runtime_checks_engines() {
+        let doctor = doctor(DefaultRegistryService::new());
+        let mut package_json = PackageJson::default();
+        package_json.engines.insert("node".to_string(), ">=22".to_string());
+
+        let findings = doctor.diagnose_runtime(&[installed("node", "20.11.0")], Some(&package_json));
+        assert_eq!(severities(&findings), vec![Severity::Warning, Severity::Error]);
+        assert!(findings[1].message.contains("engines.node `>=22`"));
+    }
+
+    #[test]
+    fn test_diagnose_managers() {
+        let doctor = doctor(DefaultRegistryService::new());
+        let mut detection = ProjectDetection::new(PathBuf::from("/project"));
+        detection.manager = Some("pnpm".to_string());
+        detection.pin = PackageManagerPin::parse("npm@9.0.0");
+
+        let findings = doctor.diagnose_managers(&[installed("npm", "10.8.2"), missing("pnpm"), missing("bun")], Some(&detection));
+        assert_eq!(severities(&findings), vec![Severity::Ok, Severity::Warning, Severity::Error]);
+        assert_eq!(findings[2].message, "This project uses pnpm but it is not installed");
+    }
+
+    #[test]
+    fn test_diagnose_lockfiles() {
+        let doctor = doctor(DefaultRegistryService::new());
+        let mut detection = ProjectDetection::new(PathBuf::from("/project"));
+        assert!(doctor.diagnose_lockfiles(Some(&detection), false).is_empty());
+        assert_eq!(severities(&doctor.diagnose_lockfiles(Some(&detection), true)), vec![Severity::Info]);
+
+        for (file, manager) in [("package-lock.json", "npm"), ("yarn.lock", "yarn")] {
+            detection.lockfiles.push(Lockfile {
+                manager: manager.to_string(),
+                path: PathBuf::from("/project").join(file),
+            });
+        }
+        let findings = doctor.diagnose_lockfiles(Some(&detection), true);
+        assert_eq!(severities(&findings), vec![Severity::Warning]);
+        assert_eq!(findings[0].message, "Conflicting lockfiles: package-lock.json (npm), yarn.lock (yarn)");
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_registry_redacts_credentials() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await.unwrap();
+            let response = "HTTP/1.1 403 Forbidden\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let url = format!("http://user:secret@{}/", address);
+        let findings = doctor(DefaultRegistryService::new().with_registry(&url, RegistrySource::Flag))
+            .diagnose_registry(Path::new("/"))
+            .await;
+        assert_eq!(severities(&findings), vec![Severity::Warning]);
+        assert!(findings[0].message.starts_with(&format!("http://***@{}/ rejected", address)));
+        assert!(!findings[0].message.contains("secret"));
+    }
+}
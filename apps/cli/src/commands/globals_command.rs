@@ -0,0 +1,50 @@
+use crate::models::{GlobalsReport, GLOBAL_MANAGERS};
+use crate::services::{GlobalsService, OutputService, PackageManagerService};
+use anyhow::Result;
+use futures::future::join_all;
+
+pub struct GlobalsCommand {
+    package_manager_service: Box<dyn PackageManagerService>,
+    globals_service: Box<dyn GlobalsService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl GlobalsCommand {
+    pub fn new(
+        package_manager_service: Box<dyn PackageManagerService>,
+        globals_service: Box<dyn GlobalsService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            package_manager_service,
+            globals_service,
+            output_service,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<()> {
+        let managers: Vec<_> = self
+            .package_manager_service
+            .get_supported_managers()
+            .into_iter()
+            .filter(|pm| GLOBAL_MANAGERS.contains(&pm.name.as_str()))
+            .collect();
+        let installed: Vec<_> = self
+            .package_manager_service
+            .check_managers(managers)
+            .await
+            .into_iter()
+            .filter(|r| r.package_manager.is_installed)
+            .collect();
+
+        if installed.is_empty() {
+            self.output_service
+                .display_info("None of npm, pnpm, yarn or bun is installed");
+            return Ok(());
+        }
+
+        let listings = join_all(installed.iter().map(|r| self.globals_service.list_globals(r))).await;
+        self.output_service.display_globals(&GlobalsReport { listings });
+        Ok(())
+    }
+}
@@ -1,21 +1,15 @@
-use crate::services::{PackageManagerService, OutputService};
+use crate::models::{Ecosystem, ManagerKind};
+use crate::services::PackageManagerService;
 use anyhow::Result;
 use colored::Colorize;
 
 pub struct ListCommand {
     package_manager_service: Box<dyn PackageManagerService>,
-    output_service: Box<dyn OutputService>,
 }
 
 impl ListCommand {
-    pub fn new(
-        package_manager_service: Box<dyn PackageManagerService>,
-        output_service: Box<dyn OutputService>,
-    ) -> Self {
-        Self {
-            package_manager_service,
-            output_service,
-        }
+    pub fn new(package_manager_service: Box<dyn PackageManagerService>) -> Self {
+        Self { package_manager_service }
     }
 
     pub async fn execute(&self) -> Result<()> {
@@ -24,15 +18,37 @@ impl ListCommand {
         println!("{}", "📋 Supported Package Managers:".bright_blue().bold());
         println!();
 
-        for manager in managers {
-            println!(
-                "{} {} - {}",
-                "•".bright_cyan(),
-                manager.get_display_name().bright_white().bold(),
-                manager.description.dimmed()
-            );
-            println!("  Command: {}", manager.command.bright_green());
+        for ecosystem in Ecosystem::ALL {
+            if !managers.iter().any(|pm| pm.ecosystem == ecosystem) {
+                continue;
+            }
+
+            println!("{}", format!("── {} ──", ecosystem.get_display_name()).bright_blue().bold());
             println!();
+
+            for kind in ManagerKind::ALL {
+                let group: Vec<_> = managers
+                    .iter()
+                    .filter(|pm| pm.ecosystem == ecosystem && pm.kind == kind)
+                    .collect();
+                if group.is_empty() {
+                    continue;
+                }
+
+                println!("{}", format!("{}:", kind.get_display_name()).bright_magenta().bold());
+                println!();
+
+                for manager in group {
+                    println!(
+                        "{} {} - {}",
+                        "•".bright_cyan(),
+                        manager.get_display_name().bright_white().bold(),
+                        manager.description.dimmed()
+                    );
+                    println!("  Command: {}", manager.command.bright_green());
+                    println!();
+                }
+            }
         }
 
         Ok(())
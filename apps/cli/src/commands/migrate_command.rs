@@ -0,0 +1,102 @@
+use crate::models::{Dialect, MigrationTarget, AGNOSTIC_MANAGERS};
+use crate::services::{DetectionService, MigrationService, OutputService, PackageManagerService, ProcessService};
+use crate::utils::constants::*;
+use anyhow::Result;
+
+/// Moves a project to another package manager and installs with it.
+pub struct MigrateCommand {
+    detection_service: Box<dyn DetectionService>,
+    package_manager_service: Box<dyn PackageManagerService>,
+    migration_service: Box<dyn MigrationService>,
+    process_service: Box<dyn ProcessService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl MigrateCommand {
+    pub fn new(
+        detection_service: Box<dyn DetectionService>,
+        package_manager_service: Box<dyn PackageManagerService>,
+        migration_service: Box<dyn MigrationService>,
+        process_service: Box<dyn ProcessService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            detection_service,
+            package_manager_service,
+            migration_service,
+            process_service,
+            output_service,
+        }
+    }
+
+    /// Applies the migration and returns the install's exit code. With
+    /// `dry_run` the planned changes are only printed.
+    pub async fn execute(&self, to: &str, dry_run: bool) -> Result<u8> {
+        if !AGNOSTIC_MANAGERS.contains(&to) {
+            self.output_service.display_error(&format!(
+                "Cannot migrate to '{}'; expected one of {}",
+                to,
+                AGNOSTIC_MANAGERS.join(", ")
+            ));
+            return Ok(EXIT_ERROR);
+        }
+
+        let directory = std::env::current_dir()?;
+        let detection = self.detection_service.detect(&directory)?;
+        if !detection.directory.join("package.json").is_file() {
+            self.output_service
+                .display_error(&format!("No package.json found in {}", detection.directory.display()));
+            return Ok(EXIT_ERROR);
+        }
+
+        // The version pinned in packageManager is the installed one.
+        let manager = self
+            .package_manager_service
+            .get_supported_managers()
+            .into_iter()
+            .find(|pm| pm.name == to);
+        let result = match manager {
+            Some(manager) => Some(self.package_manager_service.check_manager(&manager).await?),
+            None => None,
+        };
+        let version = result
+            .filter(|r| r.success)
+            .and_then(|r| r.package_manager.parsed_version);
+        let Some((version, dialect)) = version.and_then(|v| Dialect::from_manager(to, &v).map(|d| (v, d))) else {
+            self.output_service
+                .display_error(&format!("{} is not installed; install it before migrating", to));
+            return Ok(EXIT_ERROR);
+        };
+
+        let target = MigrationTarget {
+            manager: to.to_string(),
+            version,
+            dialect,
+        };
+        let plan = match self.migration_service.plan(&detection, &target) {
+            Ok(plan) => plan,
+            Err(e) => {
+                self.output_service.display_error(&format!("{:#}", e));
+                return Ok(EXIT_ERROR);
+            }
+        };
+
+        self.output_service.display_migration_plan(&plan, dry_run);
+        if dry_run || plan.is_empty() {
+            return Ok(EXIT_SUCCESS);
+        }
+
+        self.migration_service.apply(&plan)?;
+        self.output_service.display_native_command(&plan.install, false);
+        let code = self.process_service.run(&plan.install, Some(&plan.directory)).await?;
+        if code == EXIT_SUCCESS {
+            self.output_service.display_success(&format!("Migrated to {}", to));
+        } else {
+            self.output_service.display_error(&format!(
+                "{} failed; the old lockfile is kept as a .bak next to it",
+                plan.install.get_display()
+            ));
+        }
+        Ok(code)
+    }
+}
@@ -3,9 +3,33 @@ pub mod create_command;
 pub mod init_command;
 pub mod list_command;
 pub mod version_command;
+pub mod detect_command;
+pub mod cache_command;
+pub mod registry_command;
+pub mod globals_command;
+pub mod cache_usage_command;
+pub mod doctor_command;
+pub mod snapshot_command;
+pub mod verify_command;
+pub mod run_command;
+pub mod translate_command;
+pub mod migrate_command;
+pub mod workspace_command;
 
 pub use check_command::*;
 pub use create_command::*;
 pub use init_command::*;
 pub use list_command::*;
-pub use version_command::*; 
\ No newline at end of file
+pub use version_command::*; 
+pub use detect_command::*;
+pub use cache_command::*;
+pub use registry_command::*;
+pub use globals_command::*;
+pub use cache_usage_command::*;
+pub use doctor_command::*;
+pub use snapshot_command::*;
+pub use verify_command::*;
+pub use run_command::*;
+pub use translate_command::*;
+pub use migrate_command::*;
+pub use workspace_command::*;
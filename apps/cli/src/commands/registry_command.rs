@@ -0,0 +1,31 @@
+use crate::services::{OutputService, RegistryService};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct RegistryCommand {
+    registry_service: Box<dyn RegistryService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl RegistryCommand {
+    pub fn new(
+        registry_service: Box<dyn RegistryService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            registry_service,
+            output_service,
+        }
+    }
+
+    pub async fn execute(&self, directory: Option<&Path>) -> Result<()> {
+        let directory = match directory {
+            Some(directory) => directory.to_path_buf(),
+            None => std::env::current_dir()?,
+        };
+
+        let report = self.registry_service.inspect(&directory)?;
+        self.output_service.display_registry_report(&report);
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use crate::models::{DetectionSource, ManagerAction};
+use crate::services::{DetectionService, OutputService, ProcessService};
+use crate::utils::constants::*;
+use anyhow::Result;
+
+/// `install`, `add`, `remove` and `run`, translated to the project's manager.
+pub struct RunCommand {
+    detection_service: Box<dyn DetectionService>,
+    process_service: Box<dyn ProcessService>,
+    output_service: Box<dyn OutputService>,
+    default_manager: String,
+}
+
+impl RunCommand {
+    pub fn new(
+        detection_service: Box<dyn DetectionService>,
+        process_service: Box<dyn ProcessService>,
+        output_service: Box<dyn OutputService>,
+        default_manager: String,
+    ) -> Self {
+        Self {
+            detection_service,
+            process_service,
+            output_service,
+            default_manager,
+        }
+    }
+
+    /// Runs the native command and returns its exit code, or only prints it
+    /// when `print` is set.
+    pub async fn execute(&self, action: &ManagerAction, forwarded: &[String], print: bool) -> Result<u8> {
+        let directory = std::env::current_dir()?;
+        let detection = self.detection_service.detect(&directory)?;
+        for warning in &detection.warnings {
+            self.output_service.display_warning(warning);
+        }
+        // Without a pin, the lockfile that happens to win precedence would
+        // decide, and the others would silently go stale.
+        if detection.pin.is_none() && detection.get_lockfile_managers().len() > 1 {
+            self.output_service.display_error(
+                "Not running with conflicting lockfiles; delete the ones you do not use, or pin a manager with the packageManager field",
+            );
+            return Ok(EXIT_ERROR);
+        }
+
+        let (manager, source) = match &detection.manager {
+            Some(manager) => (manager.clone(), detection.source),
+            None => (self.default_manager.clone(), None),
+        };
+
+        let command = match action.to_native(&manager, forwarded) {
+            Ok(command) => command,
+            Err(e) => {
+                let origin = match source {
+                    Some(DetectionSource::PackageManagerField) => "the packageManager field",
+                    Some(DetectionSource::Lockfile) => "the lockfile",
+                    None => "settings.default_package_manager",
+                };
+                self.output_service
+                    .display_error(&format!("Package manager from {}: {}", origin, e));
+                return Ok(EXIT_ERROR);
+            }
+        };
+
+        self.output_service.display_native_command(&command, print);
+        if print {
+            return Ok(EXIT_SUCCESS);
+        }
+        self.process_service.run(&command, None).await
+    }
+}
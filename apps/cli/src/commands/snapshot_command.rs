@@ -0,0 +1,72 @@
+use crate::models::Ecosystem;
+use crate::services::{OutputService, PackageManagerService, SnapshotService};
+use crate::utils::constants::*;
+use anyhow::Result;
+use std::path::Path;
+
+pub struct SnapshotCommand {
+    package_manager_service: Box<dyn PackageManagerService>,
+    snapshot_service: Box<dyn SnapshotService>,
+    output_service: Box<dyn OutputService>,
+    ecosystems: Vec<Ecosystem>,
+}
+
+impl SnapshotCommand {
+    pub fn new(
+        package_manager_service: Box<dyn PackageManagerService>,
+        snapshot_service: Box<dyn SnapshotService>,
+        output_service: Box<dyn OutputService>,
+        ecosystems: Vec<Ecosystem>,
+    ) -> Self {
+        Self {
+            package_manager_service,
+            snapshot_service,
+            output_service,
+            ecosystems,
+        }
+    }
+
+    /// Prints the snapshot as JSON so it can be redirected to a file.
+    pub async fn snapshot(&self, ecosystem: Option<&str>) -> Result<u8> {
+        let ecosystems = match ecosystem.map(Ecosystem::parse_list) {
+            Some(Ok(ecosystems)) => ecosystems,
+            Some(Err(e)) => {
+                self.output_service.display_error(&e.to_string());
+                return Ok(EXIT_ERROR);
+            }
+            None => self.ecosystems.clone(),
+        };
+
+        let managers: Vec<_> = self
+            .package_manager_service
+            .get_supported_managers()
+            .into_iter()
+            .filter(|pm| ecosystems.contains(&pm.ecosystem))
+            .collect();
+        let results = self.package_manager_service.check_managers(managers).await;
+
+        let snapshot = self.snapshot_service.capture(results);
+        self.output_service.display_json(&serde_json::to_string_pretty(&snapshot)?);
+        Ok(EXIT_SUCCESS)
+    }
+
+    pub async fn diff(&self, left: &Path, right: &Path) -> Result<u8> {
+        let snapshots = self
+            .snapshot_service
+            .load(left)
+            .and_then(|l| Ok((l, self.snapshot_service.load(right)?)));
+        let (left_snapshot, right_snapshot) = match snapshots {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                self.output_service.display_error(&format!("{:#}", e));
+                return Ok(EXIT_ERROR);
+            }
+        };
+
+        let diff = left_snapshot.diff(&right_snapshot);
+        let left_label = format!("{} ({})", left.display(), left_snapshot.get_platform_display());
+        let right_label = format!("{} ({})", right.display(), right_snapshot.get_platform_display());
+        self.output_service.display_snapshot_diff(&left_label, &right_label, &diff);
+        Ok(EXIT_SUCCESS)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::models::{Dialect, ParsedCommand};
+use crate::services::OutputService;
+use crate::utils::constants::*;
+use anyhow::Result;
+
+pub struct TranslateCommand {
+    output_service: Box<dyn OutputService>,
+}
+
+impl TranslateCommand {
+    pub fn new(output_service: Box<dyn OutputService>) -> Self {
+        Self { output_service }
+    }
+
+    /// Exits with `EXIT_ERROR` when the command cannot be parsed or a target
+    /// has no counterpart for its verb. Dropped flags are only reported.
+    pub async fn execute(&self, command: &str, to: &str) -> Result<u8> {
+        let parsed = match ParsedCommand::parse(command) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.output_service.display_error(&e.to_string());
+                return Ok(EXIT_ERROR);
+            }
+        };
+        let dialects = match Dialect::parse_targets(to) {
+            Ok(dialects) => dialects,
+            Err(e) => {
+                self.output_service.display_error(&e.to_string());
+                return Ok(EXIT_ERROR);
+            }
+        };
+
+        let translations: Vec<_> = dialects.into_iter().map(|d| parsed.translate(d)).collect();
+        self.output_service.display_translations(&translations);
+
+        Ok(if translations.iter().all(|t| t.command.is_some()) {
+            EXIT_SUCCESS
+        } else {
+            EXIT_ERROR
+        })
+    }
+}
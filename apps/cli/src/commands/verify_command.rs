@@ -0,0 +1,105 @@
+use crate::models::PackageManagerCheckResult;
+use crate::services::{ConfigService, DetectionService, OutputService, PackageManagerService, YarnService};
+use crate::utils::constants::*;
+use anyhow::Result;
+
+pub struct VerifyCommand {
+    package_manager_service: Box<dyn PackageManagerService>,
+    config_service: Box<dyn ConfigService>,
+    detection_service: Box<dyn DetectionService>,
+    yarn_service: Box<dyn YarnService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl VerifyCommand {
+    pub fn new(
+        package_manager_service: Box<dyn PackageManagerService>,
+        config_service: Box<dyn ConfigService>,
+        detection_service: Box<dyn DetectionService>,
+        yarn_service: Box<dyn YarnService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            package_manager_service,
+            config_service,
+            detection_service,
+            yarn_service,
+            output_service,
+        }
+    }
+
+    /// Evaluates the `policy` section of `.fazrepo`. Exits with `EXIT_ERROR`
+    /// on any violation, and when there is no valid policy to evaluate.
+    pub async fn execute(&self) -> Result<u8> {
+        let config_path = self.config_service.get_config_path();
+        let config = match self.config_service.load_config() {
+            Ok(config) => config,
+            Err(e) => {
+                self.output_service.display_error(&format!("Cannot read {}: {:#}", config_path, e));
+                return Ok(EXIT_ERROR);
+            }
+        };
+        for warning in &config.warnings {
+            self.output_service.display_warning(&format!("{}: {}", config_path, warning));
+        }
+        let Some(policy) = config.policy else {
+            self.output_service.display_error(&format!("No policy section in {}", config_path));
+            return Ok(EXIT_ERROR);
+        };
+
+        let requirements = policy.get_requirements()?;
+        let all_managers = self.package_manager_service.get_supported_managers();
+        if let Some(unknown) = requirements
+            .iter()
+            .find(|r| !all_managers.iter().any(|pm| pm.name == r.name))
+        {
+            self.output_service.display_error(&format!(
+                "Unknown package manager '{}' in the policy of {}",
+                unknown.name, config_path
+            ));
+            return Ok(EXIT_ERROR);
+        }
+
+        let managers: Vec<_> = all_managers
+            .into_iter()
+            .filter(|pm| requirements.iter().any(|r| r.name == pm.name))
+            .collect();
+        let results = self.package_manager_service.check_managers(managers).await;
+        let results = self.apply_yarn_path(results).await;
+
+        let directory = std::env::current_dir()?;
+        let detection = self.detection_service.detect(&directory).ok();
+        let report = policy.evaluate(&results, detection.as_ref())?;
+        self.output_service.display_policy_report(&report);
+
+        Ok(if report.is_passing() { EXIT_SUCCESS } else { EXIT_ERROR })
+    }
+
+    /// A repo-local `yarnPath` release is the yarn the project runs, so
+    /// that is the one the policy applies to.
+    async fn apply_yarn_path(&self, results: Vec<PackageManagerCheckResult>) -> Vec<PackageManagerCheckResult> {
+        let yarn_path = self
+            .yarn_service
+            .find_yarnrc(&std::env::current_dir().unwrap_or_default())
+            .ok()
+            .flatten()
+            .and_then(|rc| rc.get_yarn_path())
+            .filter(|path| path.is_file());
+        let Some(yarn_path) = yarn_path else {
+            return results;
+        };
+
+        let mut updated = Vec::with_capacity(results.len());
+        for result in results {
+            if result.package_manager.name == "yarn" {
+                let mut manager = result.package_manager.clone();
+                manager.version = None;
+                manager.parsed_version = None;
+                updated.push(self.package_manager_service.check_binary(&manager, &yarn_path).await);
+            } else {
+                updated.push(result);
+            }
+        }
+        updated
+    }
+}
@@ -0,0 +1,39 @@
+use crate::services::{OutputService, WorkspaceService};
+use crate::utils::constants::*;
+use anyhow::Result;
+
+pub struct WorkspaceCommand {
+    workspace_service: Box<dyn WorkspaceService>,
+    output_service: Box<dyn OutputService>,
+}
+
+impl WorkspaceCommand {
+    pub fn new(
+        workspace_service: Box<dyn WorkspaceService>,
+        output_service: Box<dyn OutputService>,
+    ) -> Self {
+        Self {
+            workspace_service,
+            output_service,
+        }
+    }
+
+    /// Lists the packages of the workspace around the current directory.
+    pub async fn list(&self, json: bool) -> Result<u8> {
+        let directory = std::env::current_dir()?;
+        let Some(workspace) = self.workspace_service.discover(&directory)? else {
+            self.output_service.display_error(&format!(
+                "No workspace found at or above {} (looked for pnpm-workspace.yaml, package.json workspaces and nx.json)",
+                directory.display()
+            ));
+            return Ok(EXIT_ERROR);
+        };
+
+        if json {
+            self.output_service.display_json(&serde_json::to_string_pretty(&workspace)?);
+        } else {
+            self.output_service.display_workspace(&workspace);
+        }
+        Ok(EXIT_SUCCESS)
+    }
+}
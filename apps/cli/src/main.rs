@@ -6,11 +6,13 @@ mod utils;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use commands::*;
+use models::{AppConfig, ManagerAction, RegistrySource, ToolConfig};
 use services::*;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 use utils::constants::*;
 
-const VERSION: &str = env!("CARGO_PKG_VERSION");
-
 #[derive(Parser)]
 #[command(name = APP_NAME)]
 #[command(about = APP_DESCRIPTION)]
@@ -18,6 +20,15 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// Per-probe timeout in milliseconds for `--version` calls (overrides .fazrepo)
+    #[arg(long, global = true, value_name = "MS")]
+    timeout: Option<u64>,
+    /// Probe every tool instead of reusing cached check results
+    #[arg(long, global = true)]
+    no_cache: bool,
+    /// npm registry URL for version lookups (overrides .fazrepo and .npmrc)
+    #[arg(long, global = true, value_name = "URL")]
+    registry: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -27,9 +38,21 @@ enum Commands {
         /// Show detailed information including paths
         #[arg(short, long)]
         detailed: bool,
-        /// Only check specific package managers (comma-separated: npm,yarn,pnpm,bun)
+        /// Only check specific package managers or tools (comma-separated, e.g. npm,pnpm,turbo)
         #[arg(short, long)]
         only: Option<String>,
+        /// Fail unless these requirements hold (comma-separated, e.g. "pnpm>=8.6,npm>=9")
+        #[arg(short, long)]
+        require: Option<String>,
+        /// List every installation found on PATH and flag shadowed ones
+        #[arg(long)]
+        all_paths: bool,
+        /// Ecosystems to check (comma-separated: javascript,rust,python,go,php or all; overrides .fazrepo)
+        #[arg(short, long)]
+        ecosystem: Option<String>,
+        /// Compare npm, pnpm, yarn and bun with the latest versions in the npm registry
+        #[arg(long)]
+        outdated: bool,
     },
     /// Create a new project from template
     Create {
@@ -47,10 +70,151 @@ enum Commands {
     Init,
     /// List all supported package managers
     List,
+    /// Detect which package manager a project uses
+    Detect {
+        /// Project directory (defaults to the current directory)
+        path: Option<PathBuf>,
+    },
+    /// Show the registry, scopes, proxies and auth each package manager uses
+    Registry {
+        /// Project directory (defaults to the current directory)
+        path: Option<PathBuf>,
+    },
+    /// List packages installed globally by npm, pnpm, yarn and bun
+    Globals,
+    /// Show how much disk space each package manager's cache or store uses
+    CacheUsage {
+        /// Clean each cache with the manager's own command and report the space freed
+        #[arg(long)]
+        prune: bool,
+    },
+    /// Diagnose the environment and suggest fixes
+    Doctor {
+        /// Print the findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the check results with OS, arch and PATH as JSON (e.g. `fazrepo snapshot > env.json`)
+    Snapshot {
+        /// Ecosystems to include (comma-separated: javascript,rust,python,go,php or all; overrides .fazrepo)
+        #[arg(short, long)]
+        ecosystem: Option<String>,
+    },
+    /// Show the differences between two snapshots
+    Diff {
+        /// Snapshot to compare from
+        left: PathBuf,
+        /// Snapshot to compare to
+        right: PathBuf,
+    },
+    /// Check the machine and project against the policy in .fazrepo
+    Verify,
+    /// Install dependencies with the project's package manager
+    Install {
+        /// Print the translated command instead of running it
+        #[arg(long)]
+        print: bool,
+        /// Extra arguments passed to the package manager (after `--`)
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Add dependencies with the project's package manager
+    Add {
+        /// Packages to add
+        #[arg(required = true)]
+        packages: Vec<String>,
+        /// Add them as dev dependencies
+        #[arg(short = 'D', long)]
+        dev: bool,
+        /// Print the translated command instead of running it
+        #[arg(long)]
+        print: bool,
+        /// Extra arguments passed to the package manager (after `--`)
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Remove dependencies with the project's package manager
+    Remove {
+        /// Packages to remove
+        #[arg(required = true)]
+        packages: Vec<String>,
+        /// Print the translated command instead of running it
+        #[arg(long)]
+        print: bool,
+        /// Extra arguments passed to the package manager (after `--`)
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Run a package.json script with the project's package manager
+    Run {
+        /// Print the translated command instead of running it
+        #[arg(long)]
+        print: bool,
+        /// Script to run
+        script: String,
+        /// Arguments passed to the script
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Translate a package manager command to other managers (e.g. "npm install -D typescript")
+    Translate {
+        /// The command to translate, quoted
+        command: String,
+        /// Target managers (comma-separated: npm, yarn, yarn-classic, yarn-berry, pnpm, bun or all)
+        #[arg(long, default_value = "all")]
+        to: String,
+    },
+    /// Switch the project to another package manager and install with it
+    Migrate {
+        /// The package manager to migrate to (npm, yarn, pnpm or bun)
+        #[arg(long)]
+        to: String,
+        /// Print the planned file changes as a diff without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Inspect the monorepo workspace around the current directory
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+    /// Manage the cache of check results
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceAction {
+    /// List every workspace package with its path, version and internal dependencies
+    List {
+        /// Print the workspace as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove all cached check results
+    Clear,
 }
 
 struct App {
     check_command: CheckCommand,
+    detect_command: DetectCommand,
+    cache_command: CacheCommand,
+    registry_command: RegistryCommand,
+    globals_command: GlobalsCommand,
+    cache_usage_command: CacheUsageCommand,
+    doctor_command: DoctorCommand,
+    snapshot_command: SnapshotCommand,
+    verify_command: VerifyCommand,
+    run_command: RunCommand,
+    translate_command: TranslateCommand,
+    migrate_command: MigrateCommand,
+    workspace_command: WorkspaceCommand,
     create_command: CreateCommand,
     init_command: InitCommand,
     list_command: ListCommand,
@@ -58,34 +222,155 @@ struct App {
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(cli: &Cli) -> Self {
         // Initialize services with dependency injection
-        let package_manager_service = Box::new(DefaultPackageManagerService::new());
-        let project_service = Box::new(DefaultProjectService::new());
         let config_service = Box::new(DefaultConfigService::new());
         let output_service = Box::new(ColoredOutputService::new());
+        // `doctor` and `verify` report an invalid config themselves, and JSON
+        // output must stay clean.
+        let reports_config = matches!(cli.command, Some(Commands::Doctor { .. } | Commands::Snapshot { .. } | Commands::Verify));
+        let config = config_service.load_config().unwrap_or_else(|e| {
+            if !reports_config {
+                output_service.display_error(&format!("Ignoring {}: {}", CONFIG_FILE, e));
+            }
+            AppConfig::default()
+        });
+        if !reports_config {
+            for warning in &config.warnings {
+                output_service.display_warning(&format!("{}: {}", CONFIG_FILE, warning));
+            }
+        }
+        let probe_timeout = cli.timeout.unwrap_or(config.settings.probe_timeout_ms);
+
+        let cache_service = Box::new(
+            DefaultCacheService::new().with_ttl(Duration::from_secs(config.settings.cache_ttl_secs)),
+        );
+
+        let package_manager_service = DefaultPackageManagerService::new()
+            .with_probe_timeout(Duration::from_millis(probe_timeout))
+            .with_custom_tools(config.tools.iter().map(ToolConfig::to_package_manager).collect());
+        let package_manager_service = if cli.no_cache || config.settings.cache_ttl_secs == 0 {
+            Box::new(package_manager_service)
+        } else {
+            Box::new(package_manager_service.with_cache((*cache_service).clone()))
+        };
+        let registry_service = DefaultRegistryService::new().with_timeout(Duration::from_millis(probe_timeout));
+        let registry_service = Box::new(match (&cli.registry, &config.settings.npm_registry) {
+            (Some(url), _) => registry_service.with_registry(url, RegistrySource::Flag),
+            (None, Some(url)) => registry_service.with_registry(url, RegistrySource::Config),
+            (None, None) => registry_service,
+        });
+        let project_service = Box::new(
+            DefaultProjectService::new()
+                .with_configured_templates(&config.templates)
+                .with_template_directories(&config.settings.template_directories),
+        );
+        let manifest_service = Box::new(DefaultManifestService::new());
+        let detection_service = Box::new(DefaultDetectionService::new());
+        let yarn_service = Box::new(DefaultYarnService::new());
+        let globals_service = Box::new(DefaultGlobalsService::new().with_timeout(Duration::from_millis(probe_timeout)));
+        let snapshot_service = Box::new(DefaultSnapshotService::new());
+        let process_service = Box::new(DefaultProcessService::new());
+        let cache_usage_service = Box::new(DefaultCacheUsageService::new().with_timeout(Duration::from_millis(probe_timeout)));
 
         // Initialize commands with their dependencies
         let check_command = CheckCommand::new(
             package_manager_service.clone(),
+            manifest_service.clone(),
+            yarn_service.clone(),
+            registry_service.clone(),
+            Box::new(DefaultNodeVersionService::new()),
             output_service.clone(),
+            config.settings.ecosystems.clone(),
         );
-        let create_command = CreateCommand::new(
-            project_service.clone(),
+        let detect_command = DetectCommand::new(
+            detection_service.clone(),
+            package_manager_service.clone(),
             output_service.clone(),
         );
-        let init_command = InitCommand::new(
+        let cache_command = CacheCommand::new(
+            cache_service.clone(),
+            output_service.clone(),
+        );
+        let registry_command = RegistryCommand::new(
+            registry_service.clone(),
+            output_service.clone(),
+        );
+        let globals_command = GlobalsCommand::new(
+            package_manager_service.clone(),
+            globals_service.clone(),
+            output_service.clone(),
+        );
+        let cache_usage_command = CacheUsageCommand::new(
+            package_manager_service.clone(),
+            cache_usage_service.clone(),
+            output_service.clone(),
+        );
+        let doctor_command = DoctorCommand::new(
+            package_manager_service.clone(),
             config_service.clone(),
+            manifest_service.clone(),
+            detection_service.clone(),
+            registry_service.clone(),
             output_service.clone(),
+            config.settings.ecosystems.clone(),
         );
-        let list_command = ListCommand::new(
+        let snapshot_command = SnapshotCommand::new(
             package_manager_service.clone(),
+            snapshot_service.clone(),
+            output_service.clone(),
+            config.settings.ecosystems.clone(),
+        );
+        let verify_command = VerifyCommand::new(
+            package_manager_service.clone(),
+            config_service.clone(),
+            detection_service.clone(),
+            yarn_service.clone(),
+            output_service.clone(),
+        );
+        let run_command = RunCommand::new(
+            detection_service.clone(),
+            process_service.clone(),
+            output_service.clone(),
+            config.settings.default_package_manager.clone(),
+        );
+        let translate_command = TranslateCommand::new(output_service.clone());
+        let migrate_command = MigrateCommand::new(
+            detection_service.clone(),
+            package_manager_service.clone(),
+            Box::new(DefaultMigrationService::new()),
+            process_service.clone(),
+            output_service.clone(),
+        );
+        let workspace_command = WorkspaceCommand::new(
+            Box::new(DefaultWorkspaceService::new()),
+            output_service.clone(),
+        );
+        let create_command = CreateCommand::new(
+            project_service.clone(),
             output_service.clone(),
         );
+        let init_command = InitCommand::new(
+            config_service.clone(),
+            output_service.clone(),
+        );
+        let list_command = ListCommand::new(package_manager_service.clone());
         let version_command = VersionCommand::new(output_service.clone());
 
         Self {
             check_command,
+            detect_command,
+            cache_command,
+            registry_command,
+            globals_command,
+            cache_usage_command,
+            doctor_command,
+            snapshot_command,
+            verify_command,
+            run_command,
+            translate_command,
+            migrate_command,
+            workspace_command,
             create_command,
             init_command,
             list_command,
@@ -93,10 +378,18 @@ impl App {
         }
     }
 
-    async fn run(&self, cli: Cli) -> Result<()> {
+    async fn run(&self, cli: Cli) -> Result<u8> {
         match &cli.command {
-            Some(Commands::Check { detailed, only }) => {
-                self.check_command.execute(*detailed, only.as_deref()).await?
+            Some(Commands::Check { detailed, only, require, all_paths, ecosystem, outdated }) => {
+                let options = CheckOptions {
+                    detailed: *detailed,
+                    only: only.clone(),
+                    require: require.clone(),
+                    all_paths: *all_paths,
+                    ecosystem: ecosystem.clone(),
+                    outdated: *outdated,
+                };
+                return self.check_command.execute(&options).await;
             }
             Some(Commands::Create { name, template }) => {
                 self.create_command.execute(name, template.as_deref()).await?
@@ -113,21 +406,72 @@ impl App {
             Some(Commands::List) => {
                 self.list_command.execute().await?
             }
+            Some(Commands::Detect { path }) => {
+                self.detect_command.execute(path.as_deref()).await?
+            }
+            Some(Commands::Registry { path }) => {
+                self.registry_command.execute(path.as_deref()).await?
+            }
+            Some(Commands::Globals) => {
+                self.globals_command.execute().await?
+            }
+            Some(Commands::CacheUsage { prune }) => {
+                self.cache_usage_command.execute(*prune).await?
+            }
+            Some(Commands::Doctor { json }) => {
+                return self.doctor_command.execute(*json).await;
+            }
+            Some(Commands::Snapshot { ecosystem }) => {
+                return self.snapshot_command.snapshot(ecosystem.as_deref()).await;
+            }
+            Some(Commands::Diff { left, right }) => {
+                return self.snapshot_command.diff(left, right).await;
+            }
+            Some(Commands::Verify) => {
+                return self.verify_command.execute().await;
+            }
+            Some(Commands::Install { print, args }) => {
+                return self.run_command.execute(&ManagerAction::Install, args, *print).await;
+            }
+            Some(Commands::Add { packages, dev, print, args }) => {
+                let action = ManagerAction::Add { packages: packages.clone(), dev: *dev };
+                return self.run_command.execute(&action, args, *print).await;
+            }
+            Some(Commands::Remove { packages, print, args }) => {
+                let action = ManagerAction::Remove { packages: packages.clone() };
+                return self.run_command.execute(&action, args, *print).await;
+            }
+            Some(Commands::Run { print, script, args }) => {
+                let action = ManagerAction::Run { script: script.clone() };
+                return self.run_command.execute(&action, args, *print).await;
+            }
+            Some(Commands::Translate { command, to }) => {
+                return self.translate_command.execute(command, to).await;
+            }
+            Some(Commands::Migrate { to, dry_run }) => {
+                return self.migrate_command.execute(to, *dry_run).await;
+            }
+            Some(Commands::Workspace { action: WorkspaceAction::List { json } }) => {
+                return self.workspace_command.list(*json).await;
+            }
+            Some(Commands::Cache { action: CacheAction::Clear }) => {
+                self.cache_command.clear().await?
+            }
             None => {
                 // Default behavior - check package managers
-                self.check_command.execute(false, None).await?;
+                return self.check_command.execute(&CheckOptions::default()).await;
             }
         }
 
-        Ok(())
+        Ok(EXIT_SUCCESS)
     }
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
-    let app = App::new();
-    app.run(cli).await
+    let app = App::new(&cli);
+    app.run(cli).await.map(ExitCode::from)
 }
 
 #[cfg(test)]
@@ -0,0 +1,111 @@
+use crate::models::{PackageManager, PackageManagerCheckResult};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Path components of version manager shims: asdf, mise, nodenv and the
+/// like (`shims`), Volta (`.volta`) and Corepack (`corepack`). A shim picks
+/// the version from the directory it runs in, so its output cannot be
+/// cached per binary.
+const SHIM_COMPONENTS: &[&str] = &["shims", ".volta", "corepack"];
+
+/// Identifies one probe of one binary. A reinstalled or upgraded binary gets
+/// a new mtime, and a changed `.fazrepo` entry a new fingerprint, so either
+/// one invalidates the cached result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub name: String,
+    pub path: PathBuf,
+    pub modified_ns: u64,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub key: CacheKey,
+    pub cached_at: u64,
+    pub result: PackageManagerCheckResult,
+}
+
+impl CacheKey {
+    /// Builds the key for probing `manager` through `path`. Returns `None`
+    /// when the binary cannot be resolved, has no modification time or is a
+    /// version manager shim.
+    pub fn new(manager: &PackageManager, path: &Path) -> Option<Self> {
+        let resolved = std::fs::canonicalize(path).ok()?;
+        if is_shim(path) || is_shim(&resolved) {
+            return None;
+        }
+        let path = resolved;
+        let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+        let modified_ns = modified.duration_since(UNIX_EPOCH).ok()?.as_nanos() as u64;
+
+        Some(Self {
+            name: manager.name.clone(),
+            path,
+            modified_ns,
+            fingerprint: format!(
+                "{}|{}|{}",
+                manager.command,
+                manager.version_args.join(" "),
+                manager.get_version_pattern()
+            ),
+        })
+    }
+
+    /// File name the entry is stored under in the cache directory.
+    pub fn get_file_name(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        format!("{:016x}.json", hasher.finish())
+    }
+}
+
+fn is_shim(path: &Path) -> bool {
+    path.components()
+        .any(|c| matches!(c, Component::Normal(name) if SHIM_COMPONENTS.iter().any(|shim| name == *shim)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_changes_with_fingerprint() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("turbo");
+        std::fs::write(&binary, "").unwrap();
+
+        let turbo = PackageManager::new("turbo", "Turborepo", "", "turbo");
+        let key = CacheKey::new(&turbo, &binary).unwrap();
+        assert_eq!(key, CacheKey::new(&turbo, &binary).unwrap());
+        assert_eq!(key.get_file_name(), CacheKey::new(&turbo, &binary).unwrap().get_file_name());
+
+        let other = CacheKey::new(&turbo.with_version_args(vec!["-v".to_string()]), &binary).unwrap();
+        assert_ne!(key.get_file_name(), other.get_file_name());
+        assert!(CacheKey::new(&PackageManager::new("x", "x", "", "x"), &dir.path().join("missing")).is_none());
+    }
+
+    #[test]
+    fn test_shims_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let shims = dir.path().join(".asdf").join("shims");
+        std::fs::create_dir_all(&shims).unwrap();
+        std::fs::write(shims.join("pnpm"), "").unwrap();
+        let corepack = dir.path().join("lib").join("node_modules").join("corepack").join("dist");
+        std::fs::create_dir_all(&corepack).unwrap();
+        std::fs::write(corepack.join("pnpm.js"), "").unwrap();
+
+        let pnpm = PackageManager::new("pnpm", "pnpm", "", "pnpm");
+        assert!(CacheKey::new(&pnpm, &shims.join("pnpm")).is_none());
+        assert!(CacheKey::new(&pnpm, &corepack.join("pnpm.js")).is_none());
+        #[cfg(unix)]
+        {
+            let link = dir.path().join("pnpm");
+            std::os::unix::fs::symlink(corepack.join("pnpm.js"), &link).unwrap();
+            assert!(CacheKey::new(&pnpm, &link).is_none());
+        }
+    }
+}
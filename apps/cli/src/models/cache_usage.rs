@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Disk usage of one manager's cache or store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheUsage {
+    pub manager: String,
+    pub directory: Option<PathBuf>,
+    /// Bytes on disk, after pruning when `--prune` was given.
+    pub size: Option<u64>,
+    /// Bytes released by the manager's cleanup command.
+    pub freed: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheUsageReport {
+    pub usages: Vec<CacheUsage>,
+    pub pruned: bool,
+}
+
+impl CacheUsage {
+    pub fn new(manager: &str, directory: PathBuf, size: u64) -> Self {
+        Self {
+            manager: manager.to_string(),
+            directory: Some(directory),
+            size: Some(size),
+            freed: None,
+            error: None,
+        }
+    }
+
+    pub fn failed(manager: &str, directory: Option<PathBuf>, error: String) -> Self {
+        Self {
+            manager: manager.to_string(),
+            directory,
+            size: None,
+            freed: None,
+            error: Some(error),
+        }
+    }
+
+    pub fn with_freed(mut self, freed: u64) -> Self {
+        self.freed = Some(freed);
+        self
+    }
+
+    pub fn with_error(mut self, error: String) -> Self {
+        self.error = Some(error);
+        self
+    }
+}
+
+impl CacheUsageReport {
+    pub fn get_total_size(&self) -> u64 {
+        self.usages.iter().filter_map(|u| u.size).sum()
+    }
+
+    pub fn get_total_freed(&self) -> u64 {
+        self.usages.iter().filter_map(|u| u.freed).sum()
+    }
+}
+
+/// Human-readable size in binary units, e.g. `1.5 GiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
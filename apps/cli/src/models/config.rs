@@ -1,3 +1,7 @@
+use crate::models::{default_version_args, Ecosystem, CONFIGURED_TOOL_VERSION_PATTERN, ManagerKind, PackageManager, ToolchainPolicy};
+use crate::utils::constants::{DEFAULT_CACHE_TTL_SECS, DEFAULT_PROBE_TIMEOUT_MS};
+use anyhow::Result;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -8,6 +12,14 @@ pub struct AppConfig {
     pub initialized: bool,
     pub settings: AppSettings,
     pub templates: HashMap<String, String>,
+    #[serde(default)]
+    pub tools: Vec<ToolConfig>,
+    /// Team toolchain rules checked by `fazrepo verify`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<ToolchainPolicy>,
+    /// Invalid entries dropped while loading, one message each.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +29,45 @@ pub struct AppSettings {
     pub detailed_output: bool,
     pub default_template: String,
     pub project_directory: Option<PathBuf>,
+    #[serde(default = "default_probe_timeout_ms")]
+    pub probe_timeout_ms: u64,
+    /// Ecosystems `check` covers when `--ecosystem` is not given.
+    #[serde(default = "default_ecosystems")]
+    pub ecosystems: Vec<Ecosystem>,
+    /// Seconds a cached check result is reused; 0 disables the cache.
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// npm registry for version lookups; `.npmrc` is used when unset.
+    #[serde(default)]
+    pub npm_registry: Option<String>,
+    /// Manager `install`, `add`, `remove` and `run` use when the project
+    /// has no lockfile or packageManager field.
+    #[serde(default = "default_package_manager")]
+    pub default_package_manager: String,
+    /// Directories whose subdirectories are templates for `create`.
+    #[serde(default)]
+    pub template_directories: Vec<PathBuf>,
+}
+
+/// A tool declared under `tools` in `.fazrepo`. It is checked exactly like a
+/// built-in entry, and replaces the built-in when it reuses its name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub full_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_version_args")]
+    pub version_args: Vec<String>,
+    /// Regex whose first capture group is the version.
+    #[serde(default)]
+    pub version_pattern: Option<String>,
+    #[serde(default)]
+    pub kind: Option<ManagerKind>,
+    #[serde(default)]
+    pub ecosystem: Option<Ecosystem>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +84,9 @@ impl Default for AppConfig {
             initialized: false,
             settings: AppSettings::default(),
             templates: HashMap::new(),
+            tools: Vec::new(),
+            policy: None,
+            warnings: Vec::new(),
         }
     }
 }
@@ -45,10 +99,32 @@ impl Default for AppSettings {
             detailed_output: false,
             default_template: "fullstack-nextjs".to_string(),
             project_directory: None,
+            probe_timeout_ms: default_probe_timeout_ms(),
+            ecosystems: default_ecosystems(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+            npm_registry: None,
+            default_package_manager: default_package_manager(),
+            template_directories: Vec::new(),
         }
     }
 }
 
+fn default_probe_timeout_ms() -> u64 {
+    DEFAULT_PROBE_TIMEOUT_MS
+}
+
+fn default_ecosystems() -> Vec<Ecosystem> {
+    vec![Ecosystem::JavaScript]
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    DEFAULT_CACHE_TTL_SECS
+}
+
+fn default_package_manager() -> String {
+    "npm".to_string()
+}
+
 impl AppConfig {
     pub fn new() -> Self {
         Self::default()
@@ -59,12 +135,166 @@ impl AppConfig {
         self
     }
 
+    #[allow(dead_code)]
     pub fn set_project_directory(mut self, path: PathBuf) -> Self {
         self.settings.project_directory = Some(path);
         self
     }
 
+    #[allow(dead_code)]
     pub fn get_template_path(&self, name: &str) -> Option<&String> {
         self.templates.get(name)
     }
-} 
\ No newline at end of file
+
+    /// Drops invalid tools, an invalid policy and description-style template
+    /// entries so one bad entry does not throw away the rest of the file, and
+    /// records why in `warnings`.
+    pub fn remove_invalid_entries(&mut self) {
+        let mut warnings = Vec::new();
+        self.tools.retain(|tool| match tool.validate() {
+            Ok(()) => true,
+            Err(e) => {
+                warnings.push(format!("Skipped tool '{}': {:#}", tool.name, e));
+                false
+            }
+        });
+        if let Some(Err(e)) = self.policy.as_ref().map(ToolchainPolicy::validate) {
+            warnings.push(format!("Skipped the policy: {:#}", e));
+            self.policy = None;
+        }
+        // Older `fazrepo init` wrote a description for each template, not a path.
+        let mut descriptions: Vec<String> = self
+            .templates
+            .iter()
+            .filter(|(_, value)| value.contains(char::is_whitespace) && !value.contains(['/', '\\']))
+            .map(|(name, _)| name.clone())
+            .collect();
+        descriptions.sort();
+        for name in descriptions {
+            if let Some(value) = self.templates.remove(&name) {
+                warnings.push(format!(
+                    "Skipped template '{}': \"{}\" is a description written by an older `fazrepo init`, not a template directory",
+                    name, value
+                ));
+            }
+        }
+        self.warnings.extend(warnings);
+    }
+}
+
+impl ToolConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || !self.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            anyhow::bail!(
+                "Tool name '{}' may only contain letters, digits, '-' and '_'",
+                self.name
+            );
+        }
+        if self.command.trim().is_empty() {
+            anyhow::bail!("Tool '{}' has no command", self.name);
+        }
+        if let Some(pattern) = &self.version_pattern {
+            let regex = Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Tool '{}' has an invalid version_pattern: {}", self.name, e))?;
+            if regex.captures_len() < 2 {
+                anyhow::bail!(
+                    "Tool '{}' version_pattern needs a capture group for the version",
+                    self.name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_package_manager(&self) -> PackageManager {
+        let full_name = self.full_name.as_deref().unwrap_or(&self.name);
+        let description = self.description.as_deref().unwrap_or("Declared in .fazrepo");
+        let manager = PackageManager::new(&self.name, full_name, description, &self.command)
+            .with_kind(self.kind.unwrap_or(ManagerKind::Tool))
+            .with_ecosystem(self.ecosystem.unwrap_or_default())
+            .with_version_args(self.version_args.clone());
+
+        manager.with_version_pattern(self.version_pattern.as_deref().unwrap_or(CONFIGURED_TOOL_VERSION_PATTERN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::extract_version;
+
+    #[test]
+    fn test_tools_from_config() {
+        let config: AppConfig = serde_json::from_str(
+            r#"{
+                "version": "0.2.0",
+                "initialized": true,
+                "settings": {
+                    "output_format": "Text",
+                    "color_output": true,
+                    "detailed_output": false,
+                    "default_template": "fullstack-nextjs",
+                    "project_directory": null
+                },
+                "templates": {},
+                "tools": [
+                    { "name": "turbo", "command": "turbo" },
+                    { "name": "prisma", "command": "prisma", "version_args": ["-v"], "version_pattern": "^prisma\\s+: (\\S+)" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let turbo = config.tools[0].to_package_manager();
+        assert_eq!(turbo.kind, ManagerKind::Tool);
+        assert_eq!(turbo.version_args, vec!["--version"]);
+        assert_eq!(turbo.ecosystem, Ecosystem::JavaScript);
+        let (_, version) = extract_version("• Turborepo v2.0.3 (linux)\n", turbo.get_version_pattern()).unwrap();
+        assert_eq!(version.to_string(), "2.0.3");
+        assert_eq!(config.settings.ecosystems, vec![Ecosystem::JavaScript]);
+
+        let prisma = config.tools[1].to_package_manager();
+        assert_eq!(prisma.version_args, vec!["-v"]);
+        assert_eq!(prisma.get_version_pattern(), r"^prisma\s+: (\S+)");
+        assert!(config.tools.iter().all(|tool| tool.validate().is_ok()));
+    }
+
+    #[test]
+    fn test_invalid_tools_are_rejected() {
+        let tool = |name: &str, pattern: Option<&str>| ToolConfig {
+            name: name.to_string(),
+            command: "tool".to_string(),
+            full_name: None,
+            description: None,
+            version_args: default_version_args(),
+            version_pattern: pattern.map(str::to_string),
+            kind: None,
+            ecosystem: None,
+        };
+
+        assert!(tool("my tool", None).validate().is_err());
+        assert!(tool("nx", Some(r"(\d+")).validate().is_err());
+        assert!(tool("nx", Some(r"\d+\.\d+")).validate().is_err());
+        assert!(tool("nx", Some(r"(\d+\.\d+\.\d+)")).validate().is_ok());
+
+        let mut config = AppConfig {
+            tools: vec![tool("nx", None), tool("my tool", None)],
+            policy: Some(ToolchainPolicy {
+                node: Some(">=abc".to_string()),
+                ..ToolchainPolicy::default()
+            }),
+            ..AppConfig::default()
+        }
+        .with_template("fullstack-nextjs", "Full-stack Next.js application")
+        .with_template("api-express", "templates/api-express");
+        config.remove_invalid_entries();
+        assert_eq!(config.tools.len(), 1);
+        assert_eq!(config.tools[0].name, "nx");
+        assert!(config.policy.is_none());
+        assert_eq!(config.templates.keys().collect::<Vec<_>>(), vec!["api-express"]);
+        assert_eq!(config.warnings.len(), 3);
+        assert!(config.warnings[2].starts_with("Skipped template 'fullstack-nextjs'"));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::models::PackageManagerPin;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub manager: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DetectionSource {
+    PackageManagerField,
+    Lockfile,
+}
+
+/// Which package manager a project uses, and how that was decided.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDetection {
+    pub directory: PathBuf,
+    pub lockfiles: Vec<Lockfile>,
+    pub pin: Option<PackageManagerPin>,
+    pub manager: Option<String>,
+    pub source: Option<DetectionSource>,
+    pub warnings: Vec<String>,
+}
+
+impl ProjectDetection {
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            lockfiles: Vec::new(),
+            pin: None,
+            manager: None,
+            source: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn add_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    /// Distinct managers that have a lockfile, in lockfile precedence order.
+    pub fn get_lockfile_managers(&self) -> Vec<&str> {
+        let mut managers: Vec<&str> = Vec::new();
+        for lockfile in &self.lockfiles {
+            if !managers.contains(&lockfile.manager.as_str()) {
+                managers.push(&lockfile.manager);
+            }
+        }
+        managers
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// How serious a finding is. Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// The diagnostic a finding belongs to, in the order they are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticCategory {
+    Config,
+    Runtime,
+    Managers,
+    Path,
+    Lockfiles,
+    Registry,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub category: DiagnosticCategory,
+    pub severity: Severity,
+    pub message: String,
+    /// A concrete command or change that resolves the finding.
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorReport {
+    pub directory: PathBuf,
+    pub findings: Vec<Finding>,
+}
+
+impl DiagnosticCategory {
+    pub const ALL: [DiagnosticCategory; 6] = [
+        DiagnosticCategory::Config,
+        DiagnosticCategory::Runtime,
+        DiagnosticCategory::Managers,
+        DiagnosticCategory::Path,
+        DiagnosticCategory::Lockfiles,
+        DiagnosticCategory::Registry,
+    ];
+
+    pub fn get_display_name(&self) -> &'static str {
+        match self {
+            DiagnosticCategory::Config => "Configuration files",
+            DiagnosticCategory::Runtime => "Node.js runtime",
+            DiagnosticCategory::Managers => "Package managers",
+            DiagnosticCategory::Path => "PATH",
+            DiagnosticCategory::Lockfiles => "Lockfiles",
+            DiagnosticCategory::Registry => "Registry",
+        }
+    }
+}
+
+impl Finding {
+    pub fn new(category: DiagnosticCategory, severity: Severity, message: String) -> Self {
+        Self {
+            category,
+            severity,
+            message,
+            fix: None,
+        }
+    }
+
+    pub fn ok(category: DiagnosticCategory, message: String) -> Self {
+        Self::new(category, Severity::Ok, message)
+    }
+
+    pub fn with_fix(mut self, fix: String) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+impl DoctorReport {
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            findings: Vec::new(),
+        }
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+
+    pub fn get_worst_severity(&self) -> Severity {
+        self.findings
+            .iter()
+            .map(|f| f.severity)
+            .max()
+            .unwrap_or(Severity::Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_serializes_for_machines() {
+        let mut report = DoctorReport::new(PathBuf::from("/project"));
+        report.findings.push(Finding::ok(DiagnosticCategory::Config, ".fazrepo is valid".to_string()));
+        report.findings.push(
+            Finding::new(DiagnosticCategory::Lockfiles, Severity::Warning, "Conflicting lockfiles".to_string())
+                .with_fix("Delete package-lock.json".to_string()),
+        );
+
+        assert_eq!(report.get_worst_severity(), Severity::Warning);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["findings"][1]["severity"], "warning");
+        assert_eq!(json["findings"][1]["category"], "lockfiles");
+        assert_eq!(json["findings"][1]["fix"], "Delete package-lock.json");
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Managers that can install packages globally.
+pub const GLOBAL_MANAGERS: [&str; 4] = ["npm", "pnpm", "yarn", "bun"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobalPackage {
+    pub name: String,
+    pub version: String,
+}
+
+/// The global packages of one manager, or why they could not be listed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalListing {
+    pub manager: String,
+    pub manager_version: Option<String>,
+    pub packages: Vec<GlobalPackage>,
+    pub error: Option<String>,
+}
+
+/// A package installed globally through several managers at different versions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobalConflict {
+    pub name: String,
+    /// `(manager, version)` pairs in listing order.
+    pub installs: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GlobalsReport {
+    pub listings: Vec<GlobalListing>,
+}
+
+impl GlobalPackage {
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    /// Splits `name@version`, keeping the leading `@` of scoped names.
+    pub fn parse_spec(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        let at = spec.rfind('@').filter(|&at| at > 0)?;
+        let (name, version) = (&spec[..at], &spec[at + 1..]);
+        (!version.is_empty()).then(|| Self::new(name, version))
+    }
+}
+
+impl GlobalListing {
+    pub fn new(manager: &str, manager_version: Option<String>, mut packages: Vec<GlobalPackage>) -> Self {
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            manager: manager.to_string(),
+            manager_version,
+            packages,
+            error: None,
+        }
+    }
+
+    pub fn failed(manager: &str, manager_version: Option<String>, error: String) -> Self {
+        Self {
+            manager: manager.to_string(),
+            manager_version,
+            packages: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+impl GlobalsReport {
+    pub fn get_conflicts(&self) -> Vec<GlobalConflict> {
+        let mut conflicts: Vec<GlobalConflict> = Vec::new();
+        for listing in &self.listings {
+            for package in &listing.packages {
+                let install = (listing.manager.clone(), package.version.clone());
+                match conflicts.iter_mut().find(|c| c.name == package.name) {
+                    Some(conflict) => conflict.installs.push(install),
+                    None => conflicts.push(GlobalConflict {
+                        name: package.name.clone(),
+                        installs: vec![install],
+                    }),
+                }
+            }
+        }
+
+        conflicts.retain(|c| c.installs.iter().any(|(_, version)| *version != c.installs[0].1));
+        conflicts.sort_by(|a, b| a.name.cmp(&b.name));
+        conflicts
+    }
+}
+
+/// Parses the output of the manager's global list command.
+pub fn parse_global_packages(manager: &str, output: &str) -> Result<Vec<GlobalPackage>> {
+    match manager {
+        "npm" | "pnpm" => parse_json_globals(output),
+        "yarn" => Ok(parse_yarn_globals(output)),
+        "bun" => Ok(parse_bun_globals(output)),
+        _ => anyhow::bail!("{} does not install global packages", manager),
+    }
+}
+
+/// `npm ls -g --json` prints one tree, `pnpm ls -g --json` an array of them.
+fn parse_json_globals(output: &str) -> Result<Vec<GlobalPackage>> {
+    let json: Value = serde_json::from_str(output).context("Unexpected JSON output")?;
+    let trees = match json {
+        Value::Array(trees) => trees,
+        tree => vec![tree],
+    };
+
+    Ok(trees
+        .iter()
+        .filter_map(|tree| tree.get("dependencies")?.as_object())
+        .flatten()
+        .map(|(name, dependency)| {
+            let version = dependency
+                .get("version")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown");
+            GlobalPackage::new(name, version)
+        })
+        .collect())
+}
+
+/// Yarn Classic prints `info "name@version" has binaries:` per package.
+fn parse_yarn_globals(output: &str) -> Vec<GlobalPackage> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("info \""))
+        .filter_map(|rest| rest.split('"').next())
+        .filter_map(GlobalPackage::parse_spec)
+        .collect()
+}
+
+/// Bun prints a tree with one `├── name@version` line per package.
+fn parse_bun_globals(output: &str) -> Vec<GlobalPackage> {
+    output
+        .lines()
+        .filter(|line| line.contains("── "))
+        .map(|line| line.trim_start_matches(['├', '└', '│', '─', ' ']))
+        .filter_map(GlobalPackage::parse_spec)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_global_lists() {
+        let npm = r#"{"name":"lib","dependencies":{"typescript":{"version":"5.4.5"},"@vue/cli":{"version":"5.0.8"}}}"#;
+        let npm = parse_global_packages("npm", npm).unwrap();
+        assert!(npm.contains(&GlobalPackage::new("@vue/cli", "5.0.8")));
+
+        let pnpm = r#"[{"path":"/pnpm/global/5","dependencies":{"typescript":{"from":"typescript","version":"5.3.3"}}}]"#;
+        assert_eq!(
+            parse_global_packages("pnpm", pnpm).unwrap(),
+            vec![GlobalPackage::new("typescript", "5.3.3")]
+        );
+
+        let yarn = "yarn global v1.22.22\ninfo \"@angular/cli@17.0.0\" has binaries:\n   - ng\nDone in 0.05s.\n";
+        assert_eq!(
+            parse_global_packages("yarn", yarn).unwrap(),
+            vec![GlobalPackage::new("@angular/cli", "17.0.0")]
+        );
+
+        let bun = "/home/me/.bun/install/global node_modules (2)\n├── cowsay@1.6.0\n└── typescript@5.4.5\n";
+        assert_eq!(parse_global_packages("bun", bun).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_conflicts_need_different_versions() {
+        let report = GlobalsReport {
+            listings: vec![
+                GlobalListing::new("npm", None, vec![GlobalPackage::new("typescript", "5.4.5"), GlobalPackage::new("cowsay", "1.6.0")]),
+                GlobalListing::new("bun", None, vec![GlobalPackage::new("typescript", "5.3.3"), GlobalPackage::new("cowsay", "1.6.0")]),
+            ],
+        };
+
+        let conflicts = report.get_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].name, "typescript");
+        assert_eq!(conflicts[0].installs[1], ("bun".to_string(), "5.3.3".to_string()));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::models::parse_version;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// The parts of a project's `package.json` that fazrepo cares about.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageJson {
+    #[serde(skip)]
+    pub path: PathBuf,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub package_manager: Option<String>,
+    #[serde(default)]
+    pub engines: HashMap<String, String>,
+    #[serde(default)]
+    pub private: bool,
+    #[serde(default)]
+    pub workspaces: Option<WorkspacesField>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub peer_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub optional_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub volta: Option<VoltaConfig>,
+}
+
+/// The tool versions Volta pins in package.json.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VoltaConfig {
+    #[serde(default)]
+    pub node: Option<String>,
+}
+
+/// npm and Yarn take a list of globs; Yarn Classic also accepts
+/// `{ "packages": [...], "nohoist": [...] }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WorkspacesField {
+    Patterns(Vec<String>),
+    Object {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+/// A Corepack `packageManager` pin such as `pnpm@8.15.1+sha512.abc`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageManagerPin {
+    pub name: String,
+    pub version: Option<Version>,
+    pub raw: String,
+    pub hash: Option<String>,
+}
+
+/// How the installed manager compares to the project's pin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinCheck {
+    pub pinned: String,
+    pub installed: Option<String>,
+    pub source: PathBuf,
+    pub matches: bool,
+}
+
+impl PackageJson {
+    pub fn get_pin(&self) -> Option<PackageManagerPin> {
+        self.package_manager.as_deref().and_then(PackageManagerPin::parse)
+    }
+
+    pub fn get_workspace_patterns(&self) -> Option<&[String]> {
+        match self.workspaces.as_ref()? {
+            WorkspacesField::Patterns(patterns) | WorkspacesField::Object { packages: patterns } => Some(patterns),
+        }
+    }
+}
+
+impl PackageManagerPin {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let (name, rest) = raw.split_once('@')?;
+        if name.is_empty() {
+            return None;
+        }
+
+        let (version, hash) = match rest.split_once('+') {
+            Some((version, hash)) => (version, Some(hash.to_string())),
+            None => (rest, None),
+        };
+
+        Some(Self {
+            name: name.to_string(),
+            version: parse_version(version),
+            raw: raw.to_string(),
+            hash,
+        })
+    }
+
+    /// Compares against an installed version, ignoring build metadata.
+    pub fn matches(&self, installed: Option<&Version>) -> bool {
+        match (&self.version, installed) {
+            (Some(pinned), Some(installed)) => {
+                pinned.major == installed.major
+                    && pinned.minor == installed.minor
+                    && pinned.patch == installed.patch
+                    && pinned.pre == installed.pre
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pin_with_hash() {
+        let pin = PackageManagerPin::parse("pnpm@8.15.1+sha512.abc").unwrap();
+        assert_eq!(pin.name, "pnpm");
+        assert_eq!(pin.version, Some(Version::new(8, 15, 1)));
+        assert_eq!(pin.hash.as_deref(), Some("sha512.abc"));
+        assert!(pin.matches(Some(&Version::new(8, 15, 1))));
+        assert!(!pin.matches(Some(&Version::new(8, 15, 0))));
+        assert!(!pin.matches(None));
+    }
+
+    #[test]
+    fn test_parse_pin_rejects_missing_version() {
+        assert!(PackageManagerPin::parse("pnpm").is_none());
+        assert!(PackageManagerPin::parse("@8.0.0").is_none());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::models::{Dialect, NativeCommand};
+use semver::Version;
+use std::path::PathBuf;
+
+/// Lines of unchanged context shown around each change.
+const DIFF_CONTEXT: usize = 3;
+
+/// The manager and release a project is migrated to.
+#[derive(Debug, Clone)]
+pub struct MigrationTarget {
+    pub manager: String,
+    pub version: Version,
+    pub dialect: Dialect,
+}
+
+/// A file `fazrepo migrate` creates, rewrites or moves aside. Files are
+/// never deleted outright.
+#[derive(Debug, Clone)]
+pub enum FileChange {
+    /// `before` is `None` when the file is created.
+    Write { path: PathBuf, before: Option<String>, after: String },
+    /// Moved to `backup`, which removes it from the project.
+    Backup { path: PathBuf, backup: PathBuf },
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    pub directory: PathBuf,
+    pub from: Option<String>,
+    pub target: MigrationTarget,
+    pub changes: Vec<FileChange>,
+    /// Things the migration could not do and that need a manual look.
+    pub notes: Vec<String>,
+    pub install: NativeCommand,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Hunk(String),
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+impl FileChange {
+    pub fn get_path(&self) -> &PathBuf {
+        match self {
+            FileChange::Write { path, .. } | FileChange::Backup { path, .. } => path,
+        }
+    }
+}
+
+impl MigrationPlan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// A unified diff of two texts, line by line, with `@@` hunk headers.
+pub fn diff_lines(before: &str, after: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    // Longest common subsequence, filled from the end so the walk below can
+    // go forward.
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Each edit with the old and new line index it starts at.
+    let mut edits: Vec<(DiffLine, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push((DiffLine::Context(old[i].to_string()), i, j));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push((DiffLine::Removed(old[i].to_string()), i, j));
+            i += 1;
+        } else {
+            edits.push((DiffLine::Added(new[j].to_string()), i, j));
+            j += 1;
+        }
+    }
+
+    let is_change = |k: usize| !matches!(edits[k].0, DiffLine::Context(_));
+    let mut lines = Vec::new();
+    let mut next = 0;
+    while let Some(first) = (next..edits.len()).find(|&k| is_change(k)) {
+        let start = first.saturating_sub(DIFF_CONTEXT).max(next);
+        let mut last = first;
+        for k in first..edits.len() {
+            if is_change(k) {
+                last = k;
+            } else if k - last > 2 * DIFF_CONTEXT {
+                break;
+            }
+        }
+        let end = (last + DIFF_CONTEXT + 1).min(edits.len());
+
+        let hunk = &edits[start..end];
+        let old_count = hunk.iter().filter(|(line, _, _)| !matches!(line, DiffLine::Added(_))).count();
+        let new_count = hunk.iter().filter(|(line, _, _)| !matches!(line, DiffLine::Removed(_))).count();
+        let (_, old_start, new_start) = hunk[0];
+        lines.push(DiffLine::Hunk(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + usize::from(old_count > 0),
+            old_count,
+            new_start + usize::from(new_count > 0),
+            new_count
+        )));
+        lines.extend(hunk.iter().map(|(line, _, _)| line.clone()));
+        next = end;
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let before = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let after = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        let diff = diff_lines(before, after);
+
+        assert_eq!(diff[0], DiffLine::Hunk("@@ -1,5 +1,5 @@".to_string()));
+        assert_eq!(diff[2], DiffLine::Removed("b".to_string()));
+        assert_eq!(diff[3], DiffLine::Added("B".to_string()));
+        assert_eq!(diff[7], DiffLine::Hunk("@@ -8,3 +8,4 @@".to_string()));
+        assert_eq!(diff.last(), Some(&DiffLine::Added("k".to_string())));
+
+        let created = diff_lines("", "x\n");
+        assert_eq!(created, vec![DiffLine::Hunk("@@ -0,0 +1,1 @@".to_string()), DiffLine::Added("x".to_string())]);
+        assert!(diff_lines(before, before).is_empty());
+    }
+}
@@ -1,7 +1,47 @@
 pub mod package_manager;
 pub mod project;
 pub mod config;
+pub mod manifest;
+pub mod requirement;
+pub mod version;
+pub mod detection;
+pub mod yarn;
+pub mod cache;
+pub mod npmrc;
+pub mod outdated;
+pub mod bunfig;
+pub mod registry;
+pub mod globals;
+pub mod cache_usage;
+pub mod doctor;
+pub mod snapshot;
+pub mod policy;
+pub mod native_command;
+pub mod translation;
+pub mod migration;
+pub mod workspace;
+pub mod node_version;
 
 pub use package_manager::*;
 pub use project::*;
-pub use config::*; 
\ No newline at end of file
+pub use config::*;
+pub use manifest::*;
+pub use requirement::*;
+pub use version::*;
+pub use detection::*;
+pub use yarn::*;
+pub use cache::*;
+pub use npmrc::*;
+pub use outdated::*;
+pub use bunfig::*;
+pub use registry::*;
+pub use globals::*;
+pub use cache_usage::*;
+pub use doctor::*;
+pub use snapshot::*;
+pub use policy::*;
+pub use native_command::*;
+pub use translation::*;
+pub use migration::*;
+pub use workspace::*;
+pub use node_version::*;
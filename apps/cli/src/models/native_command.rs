@@ -0,0 +1,117 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Managers `install`, `add`, `remove` and `run` can translate to.
+pub const AGNOSTIC_MANAGERS: [&str; 4] = ["npm", "pnpm", "yarn", "bun"];
+
+/// A package-manager-agnostic action, translated to the project's manager.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManagerAction {
+    Install,
+    Add { packages: Vec<String>, dev: bool },
+    Remove { packages: Vec<String> },
+    Run { script: String },
+}
+
+/// A command line for a specific package manager.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NativeCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl ManagerAction {
+    /// The native command for `manager`, with `forwarded` passed through
+    /// unchanged after the translated arguments.
+    pub fn to_native(&self, manager: &str, forwarded: &[String]) -> Result<NativeCommand> {
+        if !AGNOSTIC_MANAGERS.contains(&manager) {
+            anyhow::bail!(
+                "'{}' is not supported; expected one of {}",
+                manager,
+                AGNOSTIC_MANAGERS.join(", ")
+            );
+        }
+
+        let mut args: Vec<String> = Vec::new();
+        match self {
+            ManagerAction::Install => args.push("install".to_string()),
+            ManagerAction::Add { packages, dev } => {
+                args.push(if manager == "npm" { "install" } else { "add" }.to_string());
+                if *dev {
+                    args.push(
+                        match manager {
+                            "npm" => "--save-dev",
+                            "bun" => "--dev",
+                            _ => "-D",
+                        }
+                        .to_string(),
+                    );
+                }
+                args.extend(packages.iter().cloned());
+            }
+            ManagerAction::Remove { packages } => {
+                args.push(if manager == "npm" { "uninstall" } else { "remove" }.to_string());
+                args.extend(packages.iter().cloned());
+            }
+            ManagerAction::Run { script } => {
+                args.push("run".to_string());
+                args.push(script.clone());
+                // npm would treat the script's flags as its own.
+                if manager == "npm" && !forwarded.is_empty() {
+                    args.push("--".to_string());
+                }
+            }
+        }
+        args.extend(forwarded.iter().cloned());
+
+        Ok(NativeCommand {
+            program: manager.to_string(),
+            args,
+        })
+    }
+}
+
+impl NativeCommand {
+    /// The command line as it would be typed into a POSIX shell.
+    pub fn get_display(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|arg| shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:@=+,%^~".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_actions() {
+        let add = ManagerAction::Add {
+            packages: vec!["react".to_string()],
+            dev: true,
+        };
+        assert_eq!(add.to_native("npm", &[]).unwrap().get_display(), "npm install --save-dev react");
+        assert_eq!(add.to_native("pnpm", &[]).unwrap().get_display(), "pnpm add -D react");
+        assert_eq!(add.to_native("bun", &[]).unwrap().get_display(), "bun add --dev react");
+
+        let run = ManagerAction::Run {
+            script: "test".to_string(),
+        };
+        let forwarded = vec!["--watch".to_string(), "a b".to_string()];
+        assert_eq!(run.to_native("npm", &forwarded).unwrap().get_display(), "npm run test -- --watch 'a b'");
+        assert_eq!(run.to_native("yarn", &forwarded).unwrap().get_display(), "yarn run test --watch 'a b'");
+
+        assert!(ManagerAction::Install.to_native("deno", &[]).is_err());
+    }
+}
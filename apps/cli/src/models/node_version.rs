@@ -0,0 +1,222 @@
+use crate::models::VersionRange;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A file that pins the project's Node.js version.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NodeVersionSource {
+    Nvmrc,
+    NodeVersion,
+    Volta,
+    ToolVersions,
+}
+
+/// A Node.js version pinned by one of the `NodeVersionSource` files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePin {
+    pub source: NodeVersionSource,
+    pub path: PathBuf,
+    /// As written, e.g. `v20.11.0`, `18` or `lts/iron`.
+    pub raw: String,
+}
+
+/// A version manager that reads a pin file, and what to run so it switches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionSwitch {
+    pub manager: String,
+    pub command: String,
+    /// Whether the manager was found on this machine.
+    pub detected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePinCheck {
+    pub pin: NodePin,
+    /// `None` when the pin is an alias such as `lts/*` that cannot be
+    /// compared offline.
+    pub matches: Option<bool>,
+    pub switch: Option<VersionSwitch>,
+}
+
+/// How the active `node` compares to every Node pin of the project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeVersionCheck {
+    pub installed: Option<String>,
+    pub pins: Vec<NodePinCheck>,
+}
+
+impl NodeVersionSource {
+    pub const ALL: [NodeVersionSource; 4] = [
+        NodeVersionSource::Nvmrc,
+        NodeVersionSource::NodeVersion,
+        NodeVersionSource::Volta,
+        NodeVersionSource::ToolVersions,
+    ];
+
+    pub fn get_display(&self) -> &'static str {
+        match self {
+            NodeVersionSource::Nvmrc => ".nvmrc",
+            NodeVersionSource::NodeVersion => ".node-version",
+            NodeVersionSource::Volta => "volta.node",
+            NodeVersionSource::ToolVersions => ".tool-versions",
+        }
+    }
+
+    /// Version managers that read this file, in order of preference, with
+    /// the command that installs and switches to the pinned version.
+    fn get_switchers(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            NodeVersionSource::Nvmrc => &[("nvm", "nvm install"), ("fnm", "fnm use --install-if-missing"), ("mise", "mise install")],
+            NodeVersionSource::NodeVersion => &[("fnm", "fnm use --install-if-missing"), ("mise", "mise install")],
+            NodeVersionSource::Volta => &[("volta", "volta install node@{version}")],
+            NodeVersionSource::ToolVersions => &[("asdf", "asdf install nodejs {version}"), ("mise", "mise install")],
+        }
+    }
+}
+
+impl NodePin {
+    fn get_normalized(&self) -> &str {
+        self.raw.trim().trim_start_matches('v')
+    }
+
+    fn get_exact(&self) -> Option<Version> {
+        Version::parse(self.get_normalized()).ok()
+    }
+
+    /// The pin as an npm range. `20` and `20.11` stand for their latest
+    /// release, so they match any version with that prefix, and ranges such
+    /// as `>=18 <21` or `20.x` are read as npm reads them. `None` for aliases.
+    pub fn get_requirement(&self) -> Option<VersionRange> {
+        let version = self.get_normalized();
+        if version.is_empty() {
+            return None;
+        }
+        VersionRange::parse(version).ok()
+    }
+
+    /// The leading components of a partial pin such as `20`, `20.11` or
+    /// `20.x`; `None` for anything else.
+    fn get_prefix(&self) -> Option<Vec<u64>> {
+        let mut prefix = Vec::new();
+        for part in self.get_normalized().split('.') {
+            match part {
+                "x" | "X" | "*" => break,
+                _ => prefix.push(part.parse().ok()?),
+            }
+        }
+        Some(prefix)
+    }
+
+    /// Whether both pins can select the same release.
+    pub fn agrees_with(&self, other: &NodePin) -> bool {
+        match (self.get_exact(), other.get_exact()) {
+            (Some(a), Some(b)) => a == b,
+            (Some(exact), None) => other.get_requirement().is_none_or(|r| r.matches(&exact)),
+            (None, Some(exact)) => self.get_requirement().is_none_or(|r| r.matches(&exact)),
+            (None, None) => match (self.get_prefix(), other.get_prefix()) {
+                (Some(a), Some(b)) => a.iter().zip(&b).all(|(a, b)| a == b),
+                // An alias such as `lts/*` cannot be resolved offline, and
+                // other ranges are only compared with exact versions.
+                _ => true,
+            },
+        }
+    }
+
+    /// The first detected manager that reads this pin, or the preferred one
+    /// when none is detected.
+    pub fn get_switch(&self, detected_managers: &[String]) -> Option<VersionSwitch> {
+        let switchers = self.source.get_switchers();
+        let (manager, command, detected) = switchers
+            .iter()
+            .find(|(manager, _)| detected_managers.iter().any(|m| m == manager))
+            .map(|(manager, command)| (manager, command, true))
+            .or_else(|| switchers.first().map(|(manager, command)| (manager, command, false)))?;
+        Some(VersionSwitch {
+            manager: manager.to_string(),
+            command: command.replace("{version}", self.get_normalized()),
+            detected,
+        })
+    }
+}
+
+impl NodeVersionCheck {
+    pub fn new(pins: Vec<NodePin>, installed: Option<&Version>, detected_managers: &[String]) -> Self {
+        let pins = pins
+            .into_iter()
+            .map(|pin| {
+                let matches = pin
+                    .get_requirement()
+                    .map(|requirement| installed.is_some_and(|v| requirement.matches(v)));
+                let switch = if matches == Some(true) { None } else { pin.get_switch(detected_managers) };
+                NodePinCheck { pin, matches, switch }
+            })
+            .collect();
+        Self {
+            installed: installed.map(|v| v.to_string()),
+            pins,
+        }
+    }
+
+    /// Pins that ask for a version another pin file rules out.
+    pub fn get_conflicting(&self) -> Vec<&NodePin> {
+        self.pins
+            .iter()
+            .map(|check| &check.pin)
+            .filter(|pin| self.pins.iter().any(|other| !pin.agrees_with(&other.pin)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pin(source: NodeVersionSource, raw: &str) -> NodePin {
+        NodePin {
+            source,
+            path: PathBuf::from(source.get_display()),
+            raw: raw.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_node_version_check() {
+        let installed = Version::new(20, 11, 1);
+        let check = NodeVersionCheck::new(
+            vec![
+                pin(NodeVersionSource::Nvmrc, "v20"),
+                pin(NodeVersionSource::ToolVersions, "20.11.1"),
+                pin(NodeVersionSource::Volta, "18.19.0"),
+                pin(NodeVersionSource::NodeVersion, "lts/iron"),
+            ],
+            Some(&installed),
+            &["mise".to_string()],
+        );
+
+        let matches: Vec<Option<bool>> = check.pins.iter().map(|c| c.matches).collect();
+        assert_eq!(matches, vec![Some(true), Some(true), Some(false), None]);
+        let conflicting: Vec<&str> = check.get_conflicting().iter().map(|p| p.raw.as_str()).collect();
+        assert_eq!(conflicting, vec!["v20", "20.11.1", "18.19.0"]);
+
+        let volta = check.pins[2].switch.as_ref().unwrap();
+        assert_eq!(volta.command, "volta install node@18.19.0");
+        assert!(!volta.detected);
+        let node_version = check.pins[3].switch.as_ref().unwrap();
+        assert_eq!((node_version.manager.as_str(), node_version.detected), ("mise", true));
+    }
+
+    #[test]
+    fn test_pins_agree() {
+        assert!(pin(NodeVersionSource::Nvmrc, "v20.11.0").agrees_with(&pin(NodeVersionSource::Volta, "20.11.0")));
+        assert!(pin(NodeVersionSource::Nvmrc, "20").agrees_with(&pin(NodeVersionSource::Volta, "20.11.0")));
+        assert!(!pin(NodeVersionSource::Nvmrc, "18").agrees_with(&pin(NodeVersionSource::Volta, "20.11.0")));
+        assert!(!pin(NodeVersionSource::Nvmrc, "18").agrees_with(&pin(NodeVersionSource::NodeVersion, "20")));
+        assert!(pin(NodeVersionSource::Nvmrc, "20").agrees_with(&pin(NodeVersionSource::NodeVersion, "20.11")));
+        assert!(pin(NodeVersionSource::Nvmrc, "v20.x").agrees_with(&pin(NodeVersionSource::NodeVersion, "20.11")));
+        assert!(!pin(NodeVersionSource::Nvmrc, "20.10").agrees_with(&pin(NodeVersionSource::NodeVersion, "20.11")));
+        assert!(pin(NodeVersionSource::Nvmrc, ">=18 <21").agrees_with(&pin(NodeVersionSource::Volta, "20.11.0")));
+        assert!(!pin(NodeVersionSource::Nvmrc, ">=18 <20").agrees_with(&pin(NodeVersionSource::Volta, "20.11.0")));
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const DEFAULT_NPM_REGISTRY: &str = "https://registry.npmjs.org/";
+
+/// One `.npmrc` file, kept as ordered `key = value` pairs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Npmrc {
+    pub path: PathBuf,
+    pub entries: Vec<(String, String)>,
+}
+
+/// Where the registry used for lookups came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RegistrySource {
+    Flag,
+    Config,
+    Environment,
+    Npmrc(PathBuf),
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedRegistry {
+    pub url: String,
+    pub source: RegistrySource,
+    /// Sent as a bearer token, never displayed.
+    #[serde(skip)]
+    pub auth_token: Option<String>,
+}
+
+impl Npmrc {
+    /// Parses npm's ini format. `${VAR}` references are expanded from `env`
+    /// like npm does; sections are not used by npm and ignored.
+    pub fn parse(path: PathBuf, content: &str, env: &HashMap<String, String>) -> Self {
+        let entries = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with(['#', ';', '[']))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| {
+                let value = value.trim().trim_matches('"');
+                (expand_env(key.trim(), env), expand_env(value, env))
+            })
+            .collect();
+
+        Self { path, entries }
+    }
+
+    /// The last value for `key`, as later lines win in npm.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn get_auth_token(&self, registry: &str) -> Option<&str> {
+        self.get(&format!("{}:_authToken", nerf_dart(registry)))
+    }
+
+    /// Any credential npm would send to `registry`: a token, basic auth, or
+    /// the legacy unscoped `_auth`/`_authToken`.
+    pub fn get_auth(&self, registry: &str) -> Option<&str> {
+        let nerf = nerf_dart(registry);
+        ["_authToken", "_auth", "_password"]
+            .iter()
+            .find_map(|key| self.get(&format!("{}:{}", nerf, key)))
+            .or_else(|| self.get("_authToken"))
+            .or_else(|| self.get("_auth"))
+    }
+
+    /// `@scope:registry` entries as `(scope, url)` pairs.
+    pub fn get_scopes(&self) -> Vec<(&str, &str)> {
+        self.entries
+            .iter()
+            .filter_map(|(key, value)| {
+                let scope = key.strip_suffix(":registry")?;
+                scope.starts_with('@').then_some((scope, value.as_str()))
+            })
+            .collect()
+    }
+}
+
+impl ResolvedRegistry {
+    pub fn new(url: &str, source: RegistrySource) -> Self {
+        Self {
+            url: normalize_registry(url),
+            source,
+            auth_token: None,
+        }
+    }
+
+    pub fn get_source_display(&self) -> String {
+        match &self.source {
+            RegistrySource::Flag => "--registry".to_string(),
+            RegistrySource::Config => "settings.npm_registry in .fazrepo".to_string(),
+            RegistrySource::Environment => "npm_config_registry".to_string(),
+            RegistrySource::Npmrc(path) => path.display().to_string(),
+            RegistrySource::Default => "default".to_string(),
+        }
+    }
+
+    /// Metadata URL for a package; scoped names keep their `@` but escape the `/`.
+    pub fn get_package_url(&self, package: &str) -> String {
+        format!("{}{}", self.url, package.replace('/', "%2f"))
+    }
+}
+
+/// Registry URLs are compared and joined with a trailing slash.
+pub fn normalize_registry(url: &str) -> String {
+    let url = url.trim();
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{}/", url)
+    }
+}
+
+/// npm keys per-registry settings by the URL without its scheme, e.g.
+/// `//npm.example.com/repo/`.
+pub fn nerf_dart(registry: &str) -> String {
+    let registry = normalize_registry(registry);
+    match registry.split_once("//") {
+        Some((_, rest)) => format!("//{}", rest),
+        None => format!("//{}", registry),
+    }
+}
+
+fn expand_env(value: &str, env: &HashMap<String, String>) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + end];
+        expanded.push_str(env.get(name).map(String::as_str).unwrap_or_default());
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_npmrc() {
+        let env = HashMap::from([("NPM_TOKEN".to_string(), "secret".to_string())]);
+        let npmrc = Npmrc::parse(
+            PathBuf::from(".npmrc"),
+            "# mirror\nregistry = https://npm.example.com/repo\n@acme:registry=https://acme.example.com/\n//npm.example.com/repo/:_authToken=${NPM_TOKEN}\n",
+            &env,
+        );
+
+        assert_eq!(npmrc.get("registry"), Some("https://npm.example.com/repo"));
+        assert_eq!(npmrc.get("@acme:registry"), Some("https://acme.example.com/"));
+        assert_eq!(npmrc.get_auth_token("https://npm.example.com/repo"), Some("secret"));
+        assert_eq!(npmrc.get_auth_token(DEFAULT_NPM_REGISTRY), None);
+    }
+
+    #[test]
+    fn test_package_url() {
+        let registry = ResolvedRegistry::new("http://localhost:4873", RegistrySource::Flag);
+        assert_eq!(registry.get_package_url("pnpm"), "http://localhost:4873/pnpm");
+        assert_eq!(
+            registry.get_package_url("@yarnpkg/cli-dist"),
+            "http://localhost:4873/@yarnpkg%2fcli-dist"
+        );
+    }
+}
@@ -1,5 +1,107 @@
+use crate::models::manifest::PinCheck;
+use crate::models::node_version::NodeVersionCheck;
+use crate::models::outdated::OutdatedCheck;
+use crate::models::requirement::RequirementResult;
+use crate::models::version::DEFAULT_VERSION_PATTERN;
+use crate::models::yarn::YarnDetails;
+use anyhow::Result;
+use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// What a registry entry is, used to group `list` and `check` output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ManagerKind {
+    #[default]
+    PackageManager,
+    Runtime,
+    VersionManager,
+    Tool,
+}
+
+impl ManagerKind {
+    pub const ALL: [ManagerKind; 4] = [
+        ManagerKind::PackageManager,
+        ManagerKind::Runtime,
+        ManagerKind::VersionManager,
+        ManagerKind::Tool,
+    ];
+
+    pub fn get_display_name(&self) -> &'static str {
+        match self {
+            ManagerKind::PackageManager => "Package Managers",
+            ManagerKind::Runtime => "Runtimes",
+            ManagerKind::VersionManager => "Version Managers",
+            ManagerKind::Tool => "Tools",
+        }
+    }
+}
+
+/// The language ecosystem an entry belongs to. Only JavaScript is checked
+/// unless others are enabled with `check --ecosystem` or in `.fazrepo`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    #[default]
+    JavaScript,
+    Rust,
+    Python,
+    Go,
+    Php,
+}
+
+impl Ecosystem {
+    pub const ALL: [Ecosystem; 5] = [
+        Ecosystem::JavaScript,
+        Ecosystem::Rust,
+        Ecosystem::Python,
+        Ecosystem::Go,
+        Ecosystem::Php,
+    ];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "javascript" | "js" | "node" => Ok(Ecosystem::JavaScript),
+            "rust" => Ok(Ecosystem::Rust),
+            "python" | "py" => Ok(Ecosystem::Python),
+            "go" | "golang" => Ok(Ecosystem::Go),
+            "php" => Ok(Ecosystem::Php),
+            _ => anyhow::bail!(
+                "Unknown ecosystem '{}' (expected javascript, rust, python, go, php or all)",
+                name.trim()
+            ),
+        }
+    }
+
+    /// Parses a comma-separated list like `python,rust`; `all` enables every ecosystem.
+    pub fn parse_list(list: &str) -> Result<Vec<Self>> {
+        let mut ecosystems = Vec::new();
+        for name in list.split(',').filter(|name| !name.trim().is_empty()) {
+            let parsed = if name.trim().eq_ignore_ascii_case("all") {
+                Ecosystem::ALL.to_vec()
+            } else {
+                vec![Self::parse(name)?]
+            };
+            for ecosystem in parsed {
+                if !ecosystems.contains(&ecosystem) {
+                    ecosystems.push(ecosystem);
+                }
+            }
+        }
+        Ok(ecosystems)
+    }
+
+    pub fn get_display_name(&self) -> &'static str {
+        match self {
+            Ecosystem::JavaScript => "JavaScript",
+            Ecosystem::Rust => "Rust",
+            Ecosystem::Python => "Python",
+            Ecosystem::Go => "Go",
+            Ecosystem::Php => "PHP",
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackageManager {
@@ -7,7 +109,25 @@ pub struct PackageManager {
     pub full_name: String,
     pub description: String,
     pub command: String,
+    #[serde(default)]
+    pub kind: ManagerKind,
+    #[serde(default)]
+    pub ecosystem: Ecosystem,
+    /// Shell script that defines `command` as a function rather than a
+    /// binary on PATH, as nvm does with `nvm.sh`.
+    #[serde(default)]
+    pub script: Option<PathBuf>,
+    /// Arguments that make `command` print its version.
+    #[serde(default = "default_version_args")]
+    pub version_args: Vec<String>,
+    /// How to find and clean the manager's download cache or store.
+    #[serde(default)]
+    pub cache_commands: Option<CacheCommands>,
     pub version: Option<String>,
+    #[serde(default)]
+    pub parsed_version: Option<Version>,
+    #[serde(default)]
+    pub version_pattern: Option<String>,
     pub path: Option<PathBuf>,
     pub is_installed: bool,
     pub is_working: bool,
@@ -20,18 +140,63 @@ impl PackageManager {
             full_name: full_name.to_string(),
             description: description.to_string(),
             command: command.to_string(),
+            kind: ManagerKind::PackageManager,
+            ecosystem: Ecosystem::JavaScript,
+            script: None,
+            version_args: default_version_args(),
+            cache_commands: None,
             version: None,
+            parsed_version: None,
+            version_pattern: None,
             path: None,
             is_installed: false,
             is_working: false,
         }
     }
 
+    pub fn with_kind(mut self, kind: ManagerKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_ecosystem(mut self, ecosystem: Ecosystem) -> Self {
+        self.ecosystem = ecosystem;
+        self
+    }
+
+    pub fn with_script(mut self, script: PathBuf) -> Self {
+        self.script = Some(script);
+        self
+    }
+
+    pub fn with_version_args(mut self, args: Vec<String>) -> Self {
+        self.version_args = args;
+        self
+    }
+
+    pub fn with_cache_commands(mut self, locate: &[&str], prune: &[&str]) -> Self {
+        self.cache_commands = Some(CacheCommands {
+            locate: locate.iter().map(|s| s.to_string()).collect(),
+            prune: prune.iter().map(|s| s.to_string()).collect(),
+        });
+        self
+    }
+
     pub fn with_version(mut self, version: String) -> Self {
         self.version = Some(version);
         self
     }
 
+    pub fn with_parsed_version(mut self, version: Version) -> Self {
+        self.parsed_version = Some(version);
+        self
+    }
+
+    pub fn with_version_pattern(mut self, pattern: &str) -> Self {
+        self.version_pattern = Some(pattern.to_string());
+        self
+    }
+
     pub fn with_path(mut self, path: PathBuf) -> Self {
         self.path = Some(path);
         self
@@ -55,6 +220,12 @@ impl PackageManager {
         self.version.clone().unwrap_or_else(|| "unknown".to_string())
     }
 
+    pub fn get_version_pattern(&self) -> &str {
+        self.version_pattern
+            .as_deref()
+            .unwrap_or(DEFAULT_VERSION_PATTERN)
+    }
+
     pub fn get_path_display(&self) -> String {
         self.path
             .as_ref()
@@ -63,11 +234,61 @@ impl PackageManager {
     }
 }
 
+/// Arguments that print a manager's cache directory and that clean it
+/// with the manager's own command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheCommands {
+    pub locate: Vec<String>,
+    pub prune: Vec<String>,
+}
+
+pub fn default_version_args() -> Vec<String> {
+    vec!["--version".to_string()]
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackageManagerCheckResult {
     pub package_manager: PackageManager,
     pub success: bool,
+    pub status: CheckStatus,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub requirement: Option<RequirementResult>,
+    #[serde(default)]
+    pub pin: Option<PinCheck>,
+    #[serde(default)]
+    pub engine: Option<RequirementResult>,
+    #[serde(default)]
+    pub installations: Vec<Installation>,
+    #[serde(default)]
+    pub yarn: Option<YarnDetails>,
+    #[serde(default)]
+    pub outdated: Option<OutdatedCheck>,
+    #[serde(default)]
+    pub node_version: Option<NodeVersionCheck>,
+    /// Whether this result was served from the check cache.
+    #[serde(default)]
+    pub from_cache: bool,
+}
+
+/// One candidate binary for a manager on PATH. The first one found is
+/// the one the shell actually runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Installation {
+    pub path: PathBuf,
+    pub target: Option<PathBuf>,
+    pub version: Option<String>,
+    pub parsed_version: Option<Version>,
+    pub status: CheckStatus,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    Ok,
+    NotInstalled,
+    VersionCheckFailed,
+    TimedOut,
 }
 
 impl PackageManagerCheckResult {
@@ -75,15 +296,119 @@ impl PackageManagerCheckResult {
         Self {
             package_manager,
             success: true,
+            status: CheckStatus::Ok,
             error_message: None,
+            requirement: None,
+            pin: None,
+            engine: None,
+            installations: Vec::new(),
+            yarn: None,
+            outdated: None,
+            node_version: None,
+            from_cache: false,
         }
     }
 
     pub fn failure(package_manager: PackageManager, error_message: String) -> Self {
+        let status = if package_manager.is_installed {
+            CheckStatus::VersionCheckFailed
+        } else {
+            CheckStatus::NotInstalled
+        };
+
         Self {
             package_manager,
             success: false,
+            status,
             error_message: Some(error_message),
+            requirement: None,
+            pin: None,
+            engine: None,
+            installations: Vec::new(),
+            yarn: None,
+            outdated: None,
+            node_version: None,
+            from_cache: false,
         }
     }
-} 
\ No newline at end of file
+
+    pub fn timed_out(package_manager: PackageManager, timeout: Duration) -> Self {
+        Self {
+            status: CheckStatus::TimedOut,
+            ..Self::failure(
+                package_manager,
+                format!("`--version` did not finish within {:.1}s", timeout.as_secs_f64()),
+            )
+        }
+    }
+
+    pub fn with_pin(mut self, pin: PinCheck) -> Self {
+        self.pin = Some(pin);
+        self
+    }
+
+    pub fn with_engine(mut self, engine: RequirementResult) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    pub fn with_yarn(mut self, yarn: YarnDetails) -> Self {
+        self.yarn = Some(yarn);
+        self
+    }
+
+    pub fn with_outdated(mut self, outdated: OutdatedCheck) -> Self {
+        self.outdated = Some(outdated);
+        self
+    }
+
+    pub fn with_node_version(mut self, node_version: NodeVersionCheck) -> Self {
+        self.node_version = Some(node_version);
+        self
+    }
+
+    pub fn mark_cached(mut self) -> Self {
+        self.from_cache = true;
+        self
+    }
+
+    pub fn with_installations(mut self, installations: Vec<Installation>) -> Self {
+        self.installations = installations;
+        self
+    }
+
+    /// Installations that lose to the active one but report a different version.
+    pub fn get_shadowed_conflicts(&self) -> Vec<&Installation> {
+        let active = self.installations.iter().find(|i| i.is_active);
+        self.installations
+            .iter()
+            .filter(|i| !i.is_active)
+            .filter(|i| match active {
+                Some(active) => i.version != active.version,
+                None => false,
+            })
+            .collect()
+    }
+
+    pub fn with_requirement(mut self, requirement: RequirementResult) -> Self {
+        self.requirement = Some(requirement);
+        self
+    }
+} 
+
+impl Installation {
+    pub fn from_result(result: PackageManagerCheckResult, is_active: bool) -> Self {
+        let pm = result.package_manager;
+        let path = pm.path.unwrap_or_default();
+        let target = std::fs::canonicalize(&path).ok().filter(|target| target != &path);
+
+        Self {
+            path,
+            target,
+            version: pm.version,
+            parsed_version: pm.parsed_version,
+            status: result.status,
+            is_active,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::models::{PackageManagerCheckResult, ProjectDetection, Requirement, VersionRange};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Toolchain rules committed under `policy` in `.fazrepo` and enforced by
+/// `fazrepo verify`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolchainPolicy {
+    /// Package managers the project may use; empty allows any.
+    #[serde(default)]
+    pub allowed_managers: Vec<String>,
+    /// Package managers the project must not use, e.g. `["npm"]`.
+    #[serde(default)]
+    pub forbidden_managers: Vec<String>,
+    /// npm-style ranges installed tools must satisfy, e.g. `{"pnpm": ">=9"}`.
+    #[serde(default)]
+    pub required_versions: BTreeMap<String, String>,
+    /// Range the installed Node.js must satisfy.
+    #[serde(default)]
+    pub node: Option<String>,
+}
+
+/// The outcome of one policy rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyCheck {
+    pub rule: String,
+    pub passed: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyReport {
+    pub checks: Vec<PolicyCheck>,
+}
+
+impl ToolchainPolicy {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(name) = self
+            .forbidden_managers
+            .iter()
+            .find(|name| self.allowed_managers.contains(name))
+        {
+            anyhow::bail!("Policy both allows and forbids '{}'", name);
+        }
+        if let Some(range) = &self.node {
+            VersionRange::parse(range)
+                .map_err(|e| anyhow::anyhow!("Policy has an invalid node range '{}': {}", range, e))?;
+        }
+        for (name, range) in &self.required_versions {
+            VersionRange::parse(range)
+                .map_err(|e| anyhow::anyhow!("Policy has an invalid range '{}' for {}: {}", range, name, e))?;
+        }
+        Ok(())
+    }
+
+    /// The version rules, with `node` first.
+    pub fn get_requirements(&self) -> Result<Vec<Requirement>> {
+        self.node
+            .iter()
+            .map(|range| ("node", range))
+            .chain(self.required_versions.iter().map(|(name, range)| (name.as_str(), range)))
+            .map(|(name, range)| Requirement::from_range(name, range))
+            .collect()
+    }
+
+    /// Checks the installed versions in `results` and the managers the
+    /// project uses according to `detection`.
+    pub fn evaluate(
+        &self,
+        results: &[PackageManagerCheckResult],
+        detection: Option<&ProjectDetection>,
+    ) -> Result<PolicyReport> {
+        let mut report = PolicyReport::default();
+
+        for requirement in self.get_requirements()? {
+            let check = match results.iter().find(|r| r.package_manager.name == requirement.name) {
+                Some(result) => {
+                    let evaluation = requirement.evaluate(result);
+                    PolicyCheck::new(&requirement.expression, evaluation.is_satisfied(), evaluation.message)
+                }
+                None => PolicyCheck::new(&requirement.expression, false, "was not checked".to_string()),
+            };
+            report.checks.push(check);
+        }
+
+        if self.allowed_managers.is_empty() && self.forbidden_managers.is_empty() {
+            return Ok(report);
+        }
+
+        let used = detection.map(get_used_managers).unwrap_or_default();
+        if used.is_empty() {
+            report.checks.push(PolicyCheck::new(
+                "managers",
+                true,
+                "No lockfile or packageManager field found".to_string(),
+            ));
+        }
+        for (manager, evidence) in used {
+            let evidence = evidence.join(", ");
+            let check = if self.forbidden_managers.contains(&manager) {
+                PolicyCheck::new(
+                    &format!("no {}", manager),
+                    false,
+                    format!("{} is forbidden but used by {}", manager, evidence),
+                )
+            } else if !self.allowed_managers.is_empty() && !self.allowed_managers.contains(&manager) {
+                PolicyCheck::new(
+                    &format!("only {}", self.allowed_managers.join(" or ")),
+                    false,
+                    format!(
+                        "{} is not one of the allowed managers ({}) but used by {}",
+                        manager,
+                        self.allowed_managers.join(", "),
+                        evidence
+                    ),
+                )
+            } else {
+                PolicyCheck::new(&manager, true, format!("used by {}", evidence))
+            };
+            report.checks.push(check);
+        }
+
+        Ok(report)
+    }
+}
+
+/// Each manager the project uses, with the lockfiles or pin that show it.
+fn get_used_managers(detection: &ProjectDetection) -> Vec<(String, Vec<String>)> {
+    let mut used: Vec<(String, Vec<String>)> = Vec::new();
+    let lockfiles = detection.lockfiles.iter().map(|lockfile| {
+        let file = lockfile.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        (lockfile.manager.clone(), file)
+    });
+    let pin = detection
+        .pin
+        .iter()
+        .map(|pin| (pin.name.clone(), format!("packageManager \"{}\"", pin.raw)));
+
+    for (manager, evidence) in lockfiles.chain(pin) {
+        match used.iter_mut().find(|(name, _)| *name == manager) {
+            Some((_, all)) => all.push(evidence),
+            None => used.push((manager, vec![evidence])),
+        }
+    }
+    used
+}
+
+impl PolicyCheck {
+    pub fn new(rule: &str, passed: bool, message: String) -> Self {
+        Self {
+            rule: rule.to_string(),
+            passed,
+            message,
+        }
+    }
+}
+
+impl PolicyReport {
+    pub fn get_violations(&self) -> Vec<&PolicyCheck> {
+        self.checks.iter().filter(|c| !c.passed).collect()
+    }
+
+    pub fn is_passing(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Lockfile, PackageManager};
+    use semver::Version;
+    use std::path::PathBuf;
+
+    fn installed(name: &str, version: &str) -> PackageManagerCheckResult {
+        let pm = PackageManager::new(name, name, name, name)
+            .with_version(version.to_string())
+            .with_parsed_version(Version::parse(version).unwrap())
+            .mark_installed()
+            .mark_working();
+        PackageManagerCheckResult::success(pm)
+    }
+
+    #[test]
+    fn test_evaluate_policy() {
+        let policy: ToolchainPolicy = serde_json::from_str(
+            r#"{
+                "forbidden_managers": ["npm"],
+                "required_versions": { "pnpm": ">=9" },
+                "node": ">=20"
+            }"#,
+        )
+        .unwrap();
+        policy.validate().unwrap();
+
+        let mut detection = ProjectDetection::new(PathBuf::from("/project"));
+        detection.lockfiles.push(Lockfile {
+            manager: "npm".to_string(),
+            path: PathBuf::from("/project/package-lock.json"),
+        });
+
+        let results = vec![installed("node", "22.3.0"), installed("pnpm", "8.15.1")];
+        let report = policy.evaluate(&results, Some(&detection)).unwrap();
+        let rules: Vec<_> = report.get_violations().iter().map(|c| c.rule.as_str()).collect();
+        assert_eq!(rules, vec!["pnpm >=9", "no npm"]);
+        assert!(report.checks[0].passed);
+
+        let allowed: ToolchainPolicy =
+            serde_json::from_str(r#"{ "allowed_managers": ["npm"], "forbidden_managers": ["npm"] }"#).unwrap();
+        assert!(allowed.validate().is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectTemplate {
@@ -11,6 +12,38 @@ pub struct ProjectTemplate {
     pub dependencies: HashMap<String, Vec<String>>,
     pub structure: ProjectStructure,
     pub config_files: Vec<ConfigFile>,
+    #[serde(default)]
+    pub source: TemplateSource,
+    /// Where the template with the same name that this one replaces comes from.
+    #[serde(default)]
+    pub overrides: Option<TemplateSource>,
+}
+
+/// Where a template was loaded from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "path", rename_all = "kebab-case")]
+pub enum TemplateSource {
+    #[default]
+    BuiltIn,
+    /// An entry of `templates` in `.fazrepo`.
+    Config(PathBuf),
+    /// A subdirectory of `settings.template_directories`.
+    Directory(PathBuf),
+    /// A subdirectory of the user template directory.
+    User(PathBuf),
+}
+
+/// The optional `template.json` of a template on disk.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TemplateManifest {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub category: Option<ProjectCategory>,
+    #[serde(default)]
+    pub technologies: Vec<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +57,34 @@ pub enum ProjectCategory {
     Tool,
 }
 
+impl TemplateSource {
+    pub fn get_display(&self) -> String {
+        match self {
+            TemplateSource::BuiltIn => "built-in".to_string(),
+            TemplateSource::Config(path) => format!("{} (.fazrepo)", path.display()),
+            TemplateSource::Directory(path) => path.display().to_string(),
+            TemplateSource::User(path) => format!("{} (user templates)", path.display()),
+        }
+    }
+}
+
+impl TemplateManifest {
+    pub fn into_template(self, name: &str, source: TemplateSource, structure: ProjectStructure) -> ProjectTemplate {
+        ProjectTemplate {
+            name: name.to_string(),
+            description: self.description.unwrap_or_else(|| "Local template".to_string()),
+            category: self.category.unwrap_or(ProjectCategory::Tool),
+            technologies: self.technologies,
+            features: self.features,
+            dependencies: HashMap::new(),
+            structure,
+            config_files: Vec::new(),
+            source,
+            overrides: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectStructure {
     pub directories: Vec<String>,
@@ -35,6 +96,10 @@ pub struct ProjectFile {
     pub path: String,
     pub content: String,
     pub is_template: bool,
+    /// Content of a file that is not UTF-8, such as an image, copied byte
+    /// for byte instead of `content`.
+    #[serde(skip)]
+    pub bytes: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +164,7 @@ impl ProjectGenerationResult {
         }
     }
 
+    #[allow(dead_code)]
     pub fn add_warning(&mut self, warning: String) {
         self.warnings.push(warning);
     }
@@ -0,0 +1,161 @@
+use crate::models::{PackageManagerCheckResult, VersionRange};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A single `--require` expression such as `pnpm>=8.6` or `bun`. Without a
+/// range the tool only has to be installed.
+#[derive(Debug, Clone)]
+pub struct Requirement {
+    pub name: String,
+    pub range: Option<VersionRange>,
+    pub expression: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RequirementStatus {
+    Satisfied,
+    Unsatisfied,
+    Missing,
+    UnknownVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementResult {
+    pub expression: String,
+    pub status: RequirementStatus,
+    pub message: String,
+}
+
+impl Requirement {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        let split = expression
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(expression.len());
+        let (name, range) = expression.split_at(split);
+
+        if name.is_empty() {
+            anyhow::bail!("Requirement '{}' must start with a package manager name", expression);
+        }
+
+        let range = range.trim();
+        let range = if range.is_empty() {
+            None
+        } else {
+            Some(VersionRange::parse(range)?)
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            range,
+            expression: expression.to_string(),
+        })
+    }
+
+    /// Builds a requirement from a name and a separate range, as found in
+    /// the `engines` block of package.json.
+    pub fn from_range(name: &str, range: &str) -> Result<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            range: Some(VersionRange::parse(range)?),
+            expression: format!("{} {}", name, range.trim()),
+        })
+    }
+
+    /// Parses a comma-separated list like `pnpm>=8.6,npm>=9`.
+    pub fn parse_list(list: &str) -> Result<Vec<Self>> {
+        list.split(',')
+            .filter(|expression| !expression.trim().is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+
+    pub fn evaluate(&self, result: &PackageManagerCheckResult) -> RequirementResult {
+        let pm = &result.package_manager;
+
+        let (status, message) = if !pm.is_installed {
+            (RequirementStatus::Missing, "not installed".to_string())
+        } else {
+            match (&self.range, &pm.parsed_version) {
+                (None, _) if result.success => {
+                    (RequirementStatus::Satisfied, "installed".to_string())
+                }
+                (None, _) => (
+                    RequirementStatus::UnknownVersion,
+                    "installed but not working".to_string(),
+                ),
+                (Some(_), None) => (
+                    RequirementStatus::UnknownVersion,
+                    "installed version could not be determined".to_string(),
+                ),
+                (Some(range), Some(version)) if range.matches(version) => (
+                    RequirementStatus::Satisfied,
+                    format!("{} satisfies {}", version, range),
+                ),
+                (Some(range), Some(version)) => (
+                    RequirementStatus::Unsatisfied,
+                    format!("{} does not satisfy {}", version, range),
+                ),
+            }
+        };
+
+        RequirementResult {
+            expression: self.expression.clone(),
+            status,
+            message,
+        }
+    }
+}
+
+impl RequirementResult {
+    pub fn is_satisfied(&self) -> bool {
+        self.status == RequirementStatus::Satisfied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PackageManager;
+    use semver::Version;
+
+    fn installed(version: &str) -> PackageManagerCheckResult {
+        let pm = PackageManager::new("pnpm", "Performant npm", "", "pnpm")
+            .with_version(version.to_string())
+            .with_parsed_version(Version::parse(version).unwrap())
+            .mark_installed()
+            .mark_working();
+        PackageManagerCheckResult::success(pm)
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let requirements = Requirement::parse_list("pnpm>=8.6, npm >=9,bun").unwrap();
+        assert_eq!(requirements.len(), 3);
+        assert_eq!(requirements[0].name, "pnpm");
+        assert_eq!(requirements[1].name, "npm");
+        assert_eq!(requirements[1].range.as_ref().unwrap().to_string(), ">=9");
+        assert!(requirements[2].range.is_none());
+        assert!(Requirement::parse(">=8").is_err());
+    }
+
+    #[test]
+    fn test_from_engine_range() {
+        let requirement = Requirement::from_range("pnpm", " >=8 <10 ").unwrap();
+        assert_eq!(requirement.expression, "pnpm >=8 <10");
+        assert_eq!(requirement.evaluate(&installed("9.1.0")).status, RequirementStatus::Satisfied);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let requirement = Requirement::parse("pnpm>=8.6").unwrap();
+        assert_eq!(requirement.evaluate(&installed("8.15.1")).status, RequirementStatus::Satisfied);
+        assert_eq!(requirement.evaluate(&installed("8.5.0")).status, RequirementStatus::Unsatisfied);
+
+        let missing = PackageManagerCheckResult::failure(
+            PackageManager::new("pnpm", "Performant npm", "", "pnpm"),
+            "Command not found".to_string(),
+        );
+        assert_eq!(requirement.evaluate(&missing).status, RequirementStatus::Missing);
+    }
+}
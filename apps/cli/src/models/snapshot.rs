@@ -0,0 +1,219 @@
+use crate::models::{CheckStatus, PackageManagerCheckResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Bumped when the snapshot layout changes incompatibly.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Everything `check` knows about a machine, written by `fazrepo snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    pub format_version: u32,
+    pub fazrepo_version: String,
+    /// Seconds since the Unix epoch.
+    pub created_at: u64,
+    pub hostname: Option<String>,
+    pub os: String,
+    pub arch: String,
+    pub path: Vec<PathBuf>,
+    pub results: Vec<PackageManagerCheckResult>,
+}
+
+/// One side of a manager comparison.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManagerState {
+    pub status: CheckStatus,
+    pub version: Option<String>,
+    pub path: Option<PathBuf>,
+}
+
+/// A manager that differs between two snapshots. `None` means the snapshot
+/// did not check it at all, e.g. because its ecosystem was not enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerDiff {
+    pub name: String,
+    pub left: Option<ManagerState>,
+    pub right: Option<ManagerState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueChange {
+    pub field: String,
+    pub left: String,
+    pub right: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub platform: Vec<ValueChange>,
+    pub managers: Vec<ManagerDiff>,
+    /// Managers that are identical in both snapshots.
+    pub unchanged: Vec<String>,
+    pub path_only_left: Vec<PathBuf>,
+    pub path_only_right: Vec<PathBuf>,
+}
+
+impl ManagerState {
+    pub fn from_result(result: &PackageManagerCheckResult) -> Self {
+        let pm = &result.package_manager;
+        Self {
+            status: result.status,
+            version: pm
+                .parsed_version
+                .as_ref()
+                .map(|v| v.to_string())
+                .or_else(|| pm.version.clone()),
+            path: pm.path.clone(),
+        }
+    }
+
+    pub fn get_display(&self) -> String {
+        match self.status {
+            CheckStatus::Ok => self.version.clone().unwrap_or_else(|| "unknown version".to_string()),
+            CheckStatus::NotInstalled => "not installed".to_string(),
+            CheckStatus::VersionCheckFailed => "version check failed".to_string(),
+            CheckStatus::TimedOut => "timed out".to_string(),
+        }
+    }
+}
+
+impl ManagerDiff {
+    /// Whether only the install location differs, which is common between
+    /// machines and usually harmless.
+    pub fn is_path_only(&self) -> bool {
+        match (&self.left, &self.right) {
+            (Some(left), Some(right)) => left.status == right.status && left.version == right.version,
+            _ => false,
+        }
+    }
+}
+
+impl EnvironmentSnapshot {
+    pub fn new(results: Vec<PackageManagerCheckResult>, path: Vec<PathBuf>, created_at: u64) -> Self {
+        Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            fazrepo_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at,
+            hostname: None,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            path,
+            results,
+        }
+    }
+
+    pub fn with_hostname(mut self, hostname: Option<String>) -> Self {
+        self.hostname = hostname;
+        self
+    }
+
+    pub fn get_platform_display(&self) -> String {
+        let platform = format!("{} {}", self.os, self.arch);
+        match &self.hostname {
+            Some(hostname) => format!("{} on {}", hostname, platform),
+            None => platform,
+        }
+    }
+
+    pub fn diff(&self, other: &EnvironmentSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+
+        for (field, left, right) in [
+            ("os", &self.os, &other.os),
+            ("arch", &self.arch, &other.arch),
+            ("fazrepo", &self.fazrepo_version, &other.fazrepo_version),
+        ] {
+            if left != right {
+                diff.platform.push(ValueChange {
+                    field: field.to_string(),
+                    left: left.clone(),
+                    right: right.clone(),
+                });
+            }
+        }
+
+        let mut names: Vec<&str> = Vec::new();
+        for result in self.results.iter().chain(&other.results) {
+            if !names.contains(&result.package_manager.name.as_str()) {
+                names.push(&result.package_manager.name);
+            }
+        }
+        for name in names {
+            let left = self.get_state(name);
+            let right = other.get_state(name);
+            if left == right {
+                diff.unchanged.push(name.to_string());
+            } else {
+                diff.managers.push(ManagerDiff {
+                    name: name.to_string(),
+                    left,
+                    right,
+                });
+            }
+        }
+
+        diff.path_only_left = self.path.iter().filter(|p| !other.path.contains(p)).cloned().collect();
+        diff.path_only_right = other.path.iter().filter(|p| !self.path.contains(p)).cloned().collect();
+        diff
+    }
+
+    fn get_state(&self, name: &str) -> Option<ManagerState> {
+        self.results
+            .iter()
+            .find(|r| r.package_manager.name == name)
+            .map(ManagerState::from_result)
+    }
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.platform.is_empty()
+            && self.managers.is_empty()
+            && self.path_only_left.is_empty()
+            && self.path_only_right.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PackageManager;
+    use semver::Version;
+
+    fn installed(name: &str, version: &str, path: &str) -> PackageManagerCheckResult {
+        let pm = PackageManager::new(name, name, name, name)
+            .with_path(PathBuf::from(path))
+            .with_version(version.to_string())
+            .with_parsed_version(Version::parse(version).unwrap())
+            .mark_installed()
+            .mark_working();
+        PackageManagerCheckResult::success(pm)
+    }
+
+    #[test]
+    fn test_diff_round_trips_through_json() {
+        let left = EnvironmentSnapshot::new(
+            vec![installed("npm", "10.8.2", "/usr/bin/npm"), installed("pnpm", "8.15.1", "/usr/bin/pnpm")],
+            vec![PathBuf::from("/usr/bin"), PathBuf::from("/home/me/.bun/bin")],
+            0,
+        );
+        let right = EnvironmentSnapshot::new(
+            vec![
+                installed("npm", "10.8.2", "/usr/bin/npm"),
+                installed("pnpm", "9.1.0", "/usr/bin/pnpm"),
+                PackageManagerCheckResult::failure(PackageManager::new("bun", "bun", "bun", "bun"), "Command not found".to_string()),
+            ],
+            vec![PathBuf::from("/usr/bin")],
+            0,
+        );
+        let right: EnvironmentSnapshot = serde_json::from_str(&serde_json::to_string(&right).unwrap()).unwrap();
+
+        let diff = left.diff(&right);
+        assert_eq!(diff.unchanged, vec!["npm"]);
+        assert_eq!(diff.managers.len(), 2);
+        assert_eq!(diff.managers[0].right.as_ref().unwrap().get_display(), "9.1.0");
+        assert!(diff.managers[1].left.is_none());
+        assert_eq!(diff.managers[1].right.as_ref().unwrap().get_display(), "not installed");
+        assert_eq!(diff.path_only_left, vec![PathBuf::from("/home/me/.bun/bin")]);
+    }
+}
@@ -0,0 +1,720 @@
+use crate::models::NativeCommand;
+use anyhow::Result;
+
+/// A package manager command-line dialect. Yarn Classic and Yarn Berry
+/// differ enough to be translated separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Npm,
+    YarnClassic,
+    YarnBerry,
+    Pnpm,
+    Bun,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verb {
+    Install,
+    FrozenInstall,
+    Add,
+    Remove,
+    Exec,
+    Dlx,
+    Run,
+    Why,
+    Outdated,
+    Audit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagKind {
+    Dev,
+    Exact,
+    Optional,
+    Peer,
+    Global,
+    Production,
+    IgnoreScripts,
+    FrozenLockfile,
+    Yes,
+    Workspace,
+    /// pnpm's `-w`: run against the workspace root, wherever it is called from.
+    WorkspaceRoot,
+}
+
+/// How a flag is written in one dialect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spelling {
+    Flag(&'static str),
+    /// A word placed between the program and the verb, e.g. `yarn global add`.
+    Prefix(&'static str),
+    Missing,
+}
+
+use Spelling::{Flag, Missing, Prefix};
+
+/// The mapping table: how each verb is spelled, in `Dialect::ALL` order.
+/// `None` means the dialect has no counterpart.
+const VERBS: &[(Verb, [Option<&[&str]>; 5])] = &[
+    (Verb::Install, [Some(&["npm", "install"]), Some(&["yarn", "install"]), Some(&["yarn", "install"]), Some(&["pnpm", "install"]), Some(&["bun", "install"])]),
+    (Verb::FrozenInstall, [Some(&["npm", "ci"]), Some(&["yarn", "install", "--frozen-lockfile"]), Some(&["yarn", "install", "--immutable"]), Some(&["pnpm", "install", "--frozen-lockfile"]), Some(&["bun", "install", "--frozen-lockfile"])]),
+    (Verb::Add, [Some(&["npm", "install"]), Some(&["yarn", "add"]), Some(&["yarn", "add"]), Some(&["pnpm", "add"]), Some(&["bun", "add"])]),
+    (Verb::Remove, [Some(&["npm", "uninstall"]), Some(&["yarn", "remove"]), Some(&["yarn", "remove"]), Some(&["pnpm", "remove"]), Some(&["bun", "remove"])]),
+    (Verb::Exec, [Some(&["npx"]), Some(&["yarn", "run"]), Some(&["yarn", "exec"]), Some(&["pnpm", "exec"]), Some(&["bunx"])]),
+    (Verb::Dlx, [Some(&["npx"]), None, Some(&["yarn", "dlx"]), Some(&["pnpm", "dlx"]), Some(&["bunx"])]),
+    (Verb::Run, [Some(&["npm", "run"]), Some(&["yarn", "run"]), Some(&["yarn", "run"]), Some(&["pnpm", "run"]), Some(&["bun", "run"])]),
+    (Verb::Why, [Some(&["npm", "explain"]), Some(&["yarn", "why"]), Some(&["yarn", "why"]), Some(&["pnpm", "why"]), Some(&["bun", "why"])]),
+    (Verb::Outdated, [Some(&["npm", "outdated"]), Some(&["yarn", "outdated"]), None, Some(&["pnpm", "outdated"]), Some(&["bun", "outdated"])]),
+    (Verb::Audit, [Some(&["npm", "audit"]), Some(&["yarn", "audit"]), Some(&["yarn", "npm", "audit"]), Some(&["pnpm", "audit"]), Some(&["bun", "audit"])]),
+];
+
+/// How each flag is spelled, in `Dialect::ALL` order. Flags with a value
+/// are written `--flag=value`, or `prefix value` for prefixes.
+const FLAGS: &[(FlagKind, [Spelling; 5])] = &[
+    (FlagKind::Dev, [Flag("-D"), Flag("-D"), Flag("-D"), Flag("-D"), Flag("--dev")]),
+    (FlagKind::Exact, [Flag("-E"), Flag("-E"), Flag("-E"), Flag("-E"), Flag("--exact")]),
+    (FlagKind::Optional, [Flag("-O"), Flag("-O"), Flag("-O"), Flag("-O"), Flag("--optional")]),
+    (FlagKind::Peer, [Flag("--save-peer"), Flag("--peer"), Flag("--peer"), Flag("--save-peer"), Flag("--peer")]),
+    (FlagKind::Global, [Flag("-g"), Prefix("global"), Missing, Flag("-g"), Flag("-g")]),
+    (FlagKind::Production, [Flag("--omit=dev"), Flag("--production"), Missing, Flag("--prod"), Flag("--production")]),
+    (FlagKind::IgnoreScripts, [Flag("--ignore-scripts"), Flag("--ignore-scripts"), Flag("--mode=skip-build"), Flag("--ignore-scripts"), Flag("--ignore-scripts")]),
+    (FlagKind::FrozenLockfile, [Missing, Flag("--frozen-lockfile"), Flag("--immutable"), Flag("--frozen-lockfile"), Flag("--frozen-lockfile")]),
+    (FlagKind::Yes, [Flag("--yes"), Missing, Missing, Missing, Missing]),
+    (FlagKind::Workspace, [Flag("--workspace"), Prefix("workspace"), Prefix("workspace"), Flag("--filter"), Flag("--filter")]),
+    (FlagKind::WorkspaceRoot, [Missing, Missing, Missing, Flag("-w"), Missing]),
+];
+
+/// Subcommands recognized in the command being translated, per program.
+const VERB_ALIASES: &[(&str, &[&str], Verb)] = &[
+    ("npm", &["install", "i", "in", "ins", "inst", "insta", "instal", "isnt", "isnta", "isntal", "isntall", "add"], Verb::Install),
+    ("npm", &["ci", "clean-install", "ic", "install-clean", "isntall-clean"], Verb::FrozenInstall),
+    ("npm", &["uninstall", "un", "unlink", "remove", "rm", "r"], Verb::Remove),
+    ("npm", &["exec", "x"], Verb::Dlx),
+    ("npm", &["run", "run-script", "rum", "urn"], Verb::Run),
+    ("npm", &["explain", "why"], Verb::Why),
+    ("npm", &["outdated"], Verb::Outdated),
+    ("npm", &["audit"], Verb::Audit),
+    ("yarn", &["install"], Verb::Install),
+    ("yarn", &["add"], Verb::Add),
+    ("yarn", &["remove"], Verb::Remove),
+    ("yarn", &["exec"], Verb::Exec),
+    ("yarn", &["dlx"], Verb::Dlx),
+    ("yarn", &["run"], Verb::Run),
+    ("yarn", &["why"], Verb::Why),
+    ("yarn", &["outdated"], Verb::Outdated),
+    ("yarn", &["audit"], Verb::Audit),
+    ("pnpm", &["install", "i"], Verb::Install),
+    ("pnpm", &["add"], Verb::Add),
+    ("pnpm", &["remove", "rm", "uninstall", "un"], Verb::Remove),
+    ("pnpm", &["exec"], Verb::Exec),
+    ("pnpm", &["dlx"], Verb::Dlx),
+    ("pnpm", &["run", "run-script"], Verb::Run),
+    ("pnpm", &["why"], Verb::Why),
+    ("pnpm", &["outdated"], Verb::Outdated),
+    ("pnpm", &["audit"], Verb::Audit),
+    ("bun", &["install", "i"], Verb::Install),
+    ("bun", &["add", "a"], Verb::Add),
+    ("bun", &["remove", "rm"], Verb::Remove),
+    ("bun", &["x"], Verb::Dlx),
+    ("bun", &["run"], Verb::Run),
+    ("bun", &["why"], Verb::Why),
+    ("bun", &["outdated"], Verb::Outdated),
+    ("bun", &["audit"], Verb::Audit),
+];
+
+/// Flags recognized in the command being translated. Single-letter flags
+/// that mean different things to different managers are tied to a program.
+const FLAG_ALIASES: &[(Option<&str>, &[&str], FlagKind)] = &[
+    (None, &["-D", "--save-dev", "--dev", "--development"], FlagKind::Dev),
+    (Some("bun"), &["-d"], FlagKind::Dev),
+    (None, &["-E", "--save-exact", "--exact"], FlagKind::Exact),
+    (None, &["-O", "--save-optional", "--optional"], FlagKind::Optional),
+    (None, &["--save-peer", "--peer"], FlagKind::Peer),
+    (Some("yarn"), &["-P"], FlagKind::Peer),
+    (None, &["-g", "--global"], FlagKind::Global),
+    (None, &["--production", "--prod", "--omit=dev"], FlagKind::Production),
+    (Some("pnpm"), &["-P"], FlagKind::Production),
+    (None, &["--ignore-scripts", "--mode=skip-build"], FlagKind::IgnoreScripts),
+    (None, &["--frozen-lockfile", "--immutable"], FlagKind::FrozenLockfile),
+    (None, &["-y", "--yes"], FlagKind::Yes),
+    (Some("npm"), &["-w", "--workspace"], FlagKind::Workspace),
+    (Some("pnpm"), &["-F", "--filter"], FlagKind::Workspace),
+    (Some("bun"), &["-F", "--filter"], FlagKind::Workspace),
+    (Some("pnpm"), &["-w", "--workspace-root"], FlagKind::WorkspaceRoot),
+];
+
+/// Scripts npm and pnpm run without `run`.
+const SCRIPT_SHORTHANDS: &[(&str, &str)] = &[
+    ("test", "test"),
+    ("t", "test"),
+    ("tst", "test"),
+    ("start", "start"),
+    ("stop", "stop"),
+    ("restart", "restart"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFlag {
+    pub kind: FlagKind,
+    pub value: Option<String>,
+    /// The flag as it was written, for notes.
+    pub written: String,
+}
+
+/// A package manager command broken down into table entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedCommand {
+    pub verb: Verb,
+    pub flags: Vec<ParsedFlag>,
+    /// Flags that are not in the table; passed through as written.
+    pub unknown_flags: Vec<String>,
+    pub positionals: Vec<String>,
+    /// Arguments for the script or binary being run.
+    pub trailing: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Translation {
+    pub dialect: Dialect,
+    /// `None` when the dialect has no counterpart for the verb.
+    pub command: Option<NativeCommand>,
+    pub notes: Vec<String>,
+}
+
+impl Dialect {
+    pub const ALL: [Dialect; 5] = [Dialect::Npm, Dialect::YarnClassic, Dialect::YarnBerry, Dialect::Pnpm, Dialect::Bun];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Dialect::Npm => "npm",
+            Dialect::YarnClassic => "yarn-classic",
+            Dialect::YarnBerry => "yarn-berry",
+            Dialect::Pnpm => "pnpm",
+            Dialect::Bun => "bun",
+        }
+    }
+
+    /// Parses a `--to` list such as `pnpm,bun` or `all`. `yarn` stands for
+    /// both Yarn dialects.
+    pub fn parse_targets(list: &str) -> Result<Vec<Dialect>> {
+        let mut dialects = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let matched: Vec<Dialect> = match name {
+                "all" => Self::ALL.to_vec(),
+                "yarn" => vec![Dialect::YarnClassic, Dialect::YarnBerry],
+                _ => Self::ALL.into_iter().filter(|d| d.get_name() == name).collect(),
+            };
+            if matched.is_empty() {
+                anyhow::bail!(
+                    "Unknown package manager '{}'; expected npm, yarn, yarn-classic, yarn-berry, pnpm, bun or all",
+                    name
+                );
+            }
+            for dialect in matched {
+                if !dialects.contains(&dialect) {
+                    dialects.push(dialect);
+                }
+            }
+        }
+        if dialects.is_empty() {
+            anyhow::bail!("No package manager to translate to");
+        }
+        Ok(dialects)
+    }
+
+    /// The dialect of an installed manager; Yarn 2 and later is Berry.
+    pub fn from_manager(manager: &str, version: &semver::Version) -> Option<Dialect> {
+        match manager {
+            "npm" => Some(Dialect::Npm),
+            "yarn" if version.major < 2 => Some(Dialect::YarnClassic),
+            "yarn" => Some(Dialect::YarnBerry),
+            "pnpm" => Some(Dialect::Pnpm),
+            "bun" => Some(Dialect::Bun),
+            _ => None,
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|d| d == self).unwrap_or_default()
+    }
+}
+
+impl Verb {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Verb::Install => "install",
+            Verb::FrozenInstall => "ci",
+            Verb::Add => "add",
+            Verb::Remove => "remove",
+            Verb::Exec => "exec",
+            Verb::Dlx => "dlx",
+            Verb::Run => "run",
+            Verb::Why => "why",
+            Verb::Outdated => "outdated",
+            Verb::Audit => "audit",
+        }
+    }
+
+    /// Whether everything after the first positional belongs to the script
+    /// or binary being run.
+    fn takes_trailing(&self) -> bool {
+        matches!(self, Verb::Run | Verb::Exec | Verb::Dlx)
+    }
+}
+
+impl FlagKind {
+    fn takes_value(&self) -> bool {
+        *self == FlagKind::Workspace
+    }
+}
+
+impl ParsedCommand {
+    /// Parses a command line such as `npm install -D typescript`.
+    pub fn parse(line: &str) -> Result<Self> {
+        let words = split_command_line(line)?;
+        let Some((program, rest)) = words.split_first() else {
+            anyhow::bail!("Nothing to translate");
+        };
+        let program = match program.as_str() {
+            "npm" | "yarn" | "pnpm" | "bun" => program.as_str(),
+            "npx" => "npm",
+            "pnpx" => "pnpm",
+            "bunx" => "bun",
+            _ => anyhow::bail!("'{}' is not an npm, yarn, pnpm or bun command", program),
+        };
+
+        let mut verb = match words[0].as_str() {
+            "npx" | "pnpx" | "bunx" => Some(Verb::Dlx),
+            _ => None,
+        };
+        let mut flags = Vec::new();
+        let mut unknown_flags = Vec::new();
+        let mut positionals = Vec::new();
+        let mut trailing = Vec::new();
+
+        let mut index = 0;
+        while index < rest.len() {
+            let word = &rest[index];
+            index += 1;
+
+            if word == "--" {
+                trailing = rest[index..].to_vec();
+                break;
+            }
+
+            if word.len() > 1 && word.starts_with('-') {
+                let (name, inline) = match lookup_flag(program, word) {
+                    Some(_) => (word.as_str(), None),
+                    None => match word.split_once('=') {
+                        Some((name, value)) => (name, Some(value.to_string())),
+                        None => (word.as_str(), None),
+                    },
+                };
+                match lookup_flag(program, name) {
+                    Some(kind) => {
+                        let value = if kind.takes_value() {
+                            let value = inline.or_else(|| rest.get(index).cloned());
+                            if value.is_none() {
+                                anyhow::bail!("{} needs a value", name);
+                            }
+                            index += usize::from(name == word.as_str());
+                            value
+                        } else {
+                            None
+                        };
+                        let written = match &value {
+                            Some(value) => format!("{} {}", name, value),
+                            None => word.clone(),
+                        };
+                        flags.push(ParsedFlag { kind, value, written });
+                    }
+                    None => unknown_flags.push(word.clone()),
+                }
+                continue;
+            }
+
+            if verb.is_none() {
+                match (program, word.as_str()) {
+                    ("yarn", "global") => {
+                        flags.push(ParsedFlag { kind: FlagKind::Global, value: None, written: "global".to_string() });
+                    }
+                    ("yarn", "workspace") => {
+                        let Some(name) = rest.get(index) else {
+                            anyhow::bail!("yarn workspace needs a workspace name");
+                        };
+                        index += 1;
+                        flags.push(ParsedFlag {
+                            kind: FlagKind::Workspace,
+                            value: Some(name.clone()),
+                            written: format!("workspace {}", name),
+                        });
+                    }
+                    ("yarn", "npm") if rest.get(index).is_some_and(|w| w == "audit") => {
+                        index += 1;
+                        verb = Some(Verb::Audit);
+                    }
+                    _ => {
+                        verb = Some(match lookup_verb(program, word) {
+                            Some(verb) => verb,
+                            None => {
+                                let shorthand = SCRIPT_SHORTHANDS
+                                    .iter()
+                                    .find(|(alias, _)| *alias == word)
+                                    .filter(|_| matches!(program, "npm" | "pnpm"));
+                                match shorthand {
+                                    Some((_, script)) => positionals.push(script.to_string()),
+                                    // yarn, pnpm and bun run unknown commands as scripts.
+                                    None if program != "npm" => positionals.push(word.clone()),
+                                    None => anyhow::bail!("Unknown npm command '{}'", word),
+                                }
+                                Verb::Run
+                            }
+                        });
+                    }
+                }
+            } else {
+                positionals.push(word.clone());
+            }
+
+            // npm keeps reading its own flags after the script name, up to `--`.
+            let verb = verb.unwrap_or(Verb::Install);
+            if verb.takes_trailing() && positionals.len() == 1 && !(program == "npm" && verb == Verb::Run) {
+                let rest = &rest[index..];
+                trailing = rest.strip_prefix(&["--".to_string()][..]).unwrap_or(rest).to_vec();
+                break;
+            }
+        }
+
+        let mut verb = match verb {
+            Some(verb) => verb,
+            None if program == "yarn" => Verb::Install,
+            None => anyhow::bail!("'{}' has no command to translate", line.trim()),
+        };
+        if verb == Verb::Install {
+            if let Some(position) = flags.iter().position(|f| f.kind == FlagKind::FrozenLockfile) {
+                flags.remove(position);
+                verb = Verb::FrozenInstall;
+            }
+        }
+        // `npm install <pkg>` and friends add packages.
+        if verb == Verb::Install && !positionals.is_empty() {
+            verb = Verb::Add;
+        }
+        if positionals.is_empty() && matches!(verb, Verb::Add | Verb::Remove | Verb::Run | Verb::Exec | Verb::Dlx) {
+            anyhow::bail!("'{}' needs a package, script or binary name", line.trim());
+        }
+
+        Ok(Self {
+            verb,
+            flags,
+            unknown_flags,
+            positionals,
+            trailing,
+        })
+    }
+
+    pub fn translate(&self, dialect: Dialect) -> Translation {
+        let spelled = VERBS
+            .iter()
+            .find(|(verb, _)| *verb == self.verb)
+            .and_then(|(_, spellings)| spellings[dialect.index()]);
+        let Some((program, verb_args)) = spelled.and_then(|words| words.split_first()) else {
+            return Translation {
+                dialect,
+                command: None,
+                notes: vec![format!("{} has no counterpart for `{}`", dialect.get_name(), self.verb.get_name())],
+            };
+        };
+
+        let mut notes = Vec::new();
+        let mut prefix = Vec::new();
+        let mut flags = Vec::new();
+        for flag in &self.flags {
+            let spelling = FLAGS
+                .iter()
+                .find(|(kind, _)| *kind == flag.kind)
+                .map(|(_, spellings)| spellings[dialect.index()])
+                .unwrap_or(Missing);
+            match (spelling, &flag.value) {
+                (Flag(name), Some(value)) => flags.push(format!("{}={}", name, value)),
+                (Flag(name), None) => flags.push(name.to_string()),
+                (Prefix(word), value) => {
+                    prefix.push(word.to_string());
+                    prefix.extend(value.iter().cloned());
+                }
+                (Missing, _) => notes.push(format!(
+                    "`{}` has no {} counterpart and was dropped",
+                    flag.written,
+                    dialect.get_name()
+                )),
+            }
+        }
+        for flag in &self.unknown_flags {
+            flags.push(flag.clone());
+            notes.push(format!("`{}` is not in the mapping table and was passed through unchanged", flag));
+        }
+
+        let mut args = prefix;
+        args.extend(verb_args.iter().map(|a| a.to_string()));
+        args.extend(flags);
+        args.extend(self.positionals.iter().cloned());
+        if !self.trailing.is_empty() {
+            if dialect == Dialect::Npm && self.verb == Verb::Run {
+                args.push("--".to_string());
+            }
+            args.extend(self.trailing.iter().cloned());
+        }
+
+        Translation {
+            dialect,
+            command: Some(NativeCommand {
+                program: program.to_string(),
+                args,
+            }),
+            notes,
+        }
+    }
+}
+
+fn lookup_verb(program: &str, word: &str) -> Option<Verb> {
+    VERB_ALIASES
+        .iter()
+        .find(|(p, words, _)| *p == program && words.contains(&word))
+        .map(|(_, _, verb)| *verb)
+}
+
+fn lookup_flag(program: &str, word: &str) -> Option<FlagKind> {
+    FLAG_ALIASES
+        .iter()
+        .find(|(p, words, _)| p.is_none_or(|p| p == program) && words.contains(&word))
+        .map(|(_, _, kind)| *kind)
+}
+
+/// Rewrites the invocations of `programs` in a shell snippet, such as a
+/// package.json script, for `dialect`. Anything that cannot be translated
+/// faithfully is left as it is and explained in the returned notes.
+pub fn rewrite_shell(shell: &str, programs: &[&str], dialect: Dialect) -> (String, Vec<String>) {
+    let mut rewritten = String::new();
+    let mut notes = Vec::new();
+    for (segment, separator) in split_shell_segments(shell) {
+        rewritten.push_str(&rewrite_segment(&segment, programs, dialect, &mut notes));
+        rewritten.push_str(&separator);
+    }
+    (rewritten, notes)
+}
+
+fn rewrite_segment(segment: &str, programs: &[&str], dialect: Dialect, notes: &mut Vec<String>) -> String {
+    let body = segment.trim();
+    let start = segment.len() - segment.trim_start().len();
+    let (leading, trailing) = (&segment[..start], &segment[start + body.len()..]);
+
+    // Leading `NAME=value` assignments stay in front of the command.
+    let mut command = body;
+    while let Some((word, rest)) = command.split_once(char::is_whitespace) {
+        let is_assignment = word
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        if !is_assignment {
+            break;
+        }
+        command = rest.trim_start();
+    }
+    let environment = &body[..body.len() - command.len()];
+
+    let mut words = command.split_whitespace();
+    if !words.next().is_some_and(|program| programs.contains(&program)) {
+        return segment.to_string();
+    }
+    if command.contains(['$', '`', '<', '>', '(', ')']) {
+        notes.push(format!("`{}` uses shell expansion or redirection and was left unchanged", command));
+        return segment.to_string();
+    }
+
+    let parsed = match ParsedCommand::parse(command) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            notes.push(format!("`{}` was left unchanged: {}", command, e));
+            return segment.to_string();
+        }
+    };
+    // `bun build`, `bun test` and `bun file.ts` are Bun itself, not scripts.
+    let is_bun_runtime = command.starts_with("bun ")
+        && parsed.verb == Verb::Run
+        && (words.next().is_none_or(|verb| lookup_verb("bun", verb).is_none())
+            || parsed.positionals[0].contains(['.', '/']));
+    if is_bun_runtime {
+        notes.push(format!("`{}` runs Bun itself and was left unchanged", command));
+        return segment.to_string();
+    }
+
+    let translation = parsed.translate(dialect);
+    notes.extend(translation.notes.iter().map(|note| format!("`{}`: {}", command, note)));
+    match translation.command {
+        Some(native) => format!("{}{}{}{}", leading, environment, native.get_display(), trailing),
+        None => segment.to_string(),
+    }
+}
+
+/// Splits at unquoted `&&`, `||`, `;` and `|`, keeping each separator.
+fn split_shell_segments(shell: &str) -> Vec<(String, String)> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = shell.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, '&' | '|') if chars.peek() == Some(&c) => {
+                chars.next();
+                segments.push((std::mem::take(&mut current), format!("{}{}", c, c)));
+            }
+            (None, ';' | '|') => segments.push((std::mem::take(&mut current), c.to_string())),
+            _ => current.push(c),
+        }
+    }
+
+    segments.push((current, String::new()));
+    segments
+}
+
+/// Splits a command line into words like a POSIX shell would, honoring
+/// single quotes, double quotes and backslash escapes.
+pub fn split_command_line(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(word) = current.take() {
+                    words.push(word);
+                }
+            }
+            '\'' => {
+                let word = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => anyhow::bail!("Unterminated single quote"),
+                    }
+                }
+            }
+            '"' => {
+                let word = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => anyhow::bail!("Unterminated double quote"),
+                        },
+                        Some(c) => word.push(c),
+                        None => anyhow::bail!("Unterminated double quote"),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    current.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    words.extend(current);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(line: &str, dialect: Dialect) -> Translation {
+        ParsedCommand::parse(line).unwrap().translate(dialect)
+    }
+
+    fn display(line: &str, dialect: Dialect) -> String {
+        translate(line, dialect).command.unwrap().get_display()
+    }
+
+    #[test]
+    fn test_translate_verbs_and_flags() {
+        assert_eq!(display("npm install -D typescript", Dialect::Pnpm), "pnpm add -D typescript");
+        assert_eq!(display("npm i --save-dev typescript", Dialect::Bun), "bun add --dev typescript");
+        assert_eq!(display("npm ci", Dialect::YarnBerry), "yarn install --immutable");
+        assert_eq!(display("pnpm install --frozen-lockfile", Dialect::Npm), "npm ci");
+        assert_eq!(display("yarn global add serve", Dialect::Npm), "npm install -g serve");
+        assert_eq!(display("npm install -g serve", Dialect::YarnClassic), "yarn global add serve");
+        assert_eq!(display("pnpm --filter web add react", Dialect::YarnBerry), "yarn workspace web add react");
+        assert_eq!(display("npm -w web run build", Dialect::Pnpm), "pnpm run --filter=web build");
+        assert_eq!(display("pnpm -w add -D typescript", Dialect::Pnpm), "pnpm add -w -D typescript");
+        assert_eq!(display("yarn npm audit", Dialect::Pnpm), "pnpm audit");
+        assert_eq!(display("npx create-vite my-app --template react", Dialect::Pnpm), "pnpm dlx create-vite my-app --template react");
+        assert_eq!(display("yarn build --watch", Dialect::Npm), "npm run build -- --watch");
+        assert_eq!(display("npm test -- --coverage", Dialect::Bun), "bun run test --coverage");
+        assert_eq!(display("yarn", Dialect::Pnpm), "pnpm install");
+    }
+
+    #[test]
+    fn test_report_what_cannot_be_translated() {
+        let outdated = translate("pnpm outdated", Dialect::YarnBerry);
+        assert!(outdated.command.is_none());
+        assert_eq!(outdated.notes, vec!["yarn-berry has no counterpart for `outdated`"]);
+
+        let production = translate("npm install --omit=dev --foo", Dialect::YarnBerry);
+        assert_eq!(production.command.unwrap().get_display(), "yarn install --foo");
+        assert_eq!(production.notes.len(), 2);
+        assert!(production.notes[0].contains("`--omit=dev` has no yarn-berry counterpart"));
+
+        let root = translate("pnpm -w run build", Dialect::Npm);
+        assert_eq!(root.command.unwrap().get_display(), "npm run build");
+        assert_eq!(root.notes, vec!["`-w` has no npm counterpart and was dropped"]);
+        let root = translate("pnpm add -D -w typescript", Dialect::YarnClassic);
+        assert_eq!(root.command.unwrap().get_display(), "yarn add -D typescript");
+        assert_eq!(root.notes.len(), 1);
+
+        assert!(ParsedCommand::parse("npm frobnicate").is_err());
+        assert!(ParsedCommand::parse("make install").is_err());
+        assert_eq!(Dialect::parse_targets("yarn,npm").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_rewrite_shell() {
+        let programs = ["npm"];
+        let (script, notes) = rewrite_shell("npm run build && NODE_ENV=test npm test -- --ci", &programs, Dialect::Pnpm);
+        assert_eq!(script, "pnpm run build && NODE_ENV=test pnpm run test --ci");
+        assert!(notes.is_empty());
+
+        let (script, notes) = rewrite_shell("npm run lint > lint.log; tsc", &programs, Dialect::Pnpm);
+        assert_eq!(script, "npm run lint > lint.log; tsc");
+        assert_eq!(notes.len(), 1);
+
+        let (script, notes) = rewrite_shell("bun build ./src && bun run test", &["bun"], Dialect::Npm);
+        assert_eq!(script, "bun build ./src && npm run test");
+        assert_eq!(notes.len(), 1);
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line(r#"npm run "my script" -- 'a b' c\ d"#).unwrap(),
+            vec!["npm", "run", "my script", "--", "a b", "c d"]
+        );
+        assert!(split_command_line("npm 'oops").is_err());
+    }
+}
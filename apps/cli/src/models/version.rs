@@ -0,0 +1,373 @@
+use anyhow::Result;
+use regex::Regex;
+use semver::{BuildMetadata, Prerelease, Version};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Matches a line that consists of a bare version number, optionally prefixed
+/// with `v`. This is what npm, yarn, pnpm and bun print for `--version`.
+pub const DEFAULT_VERSION_PATTERN: &str =
+    r"^v?(\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)$";
+
+/// The default for tools declared in `.fazrepo`, whose output is unknown:
+/// the first `x.y.z` anywhere on a line, e.g. in `Turborepo v2.0.3`.
+pub const CONFIGURED_TOOL_VERSION_PATTERN: &str =
+    r"\bv?(\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)";
+
+/// Finds the version in a tool's `--version` output. `pattern` is applied to
+/// each trimmed line and its first capture group must hold the version.
+/// Returns the matching line together with the parsed version.
+pub fn extract_version(output: &str, pattern: &str) -> Option<(String, Version)> {
+    let regex = Regex::new(pattern).ok()?;
+
+    output.lines().map(str::trim).find_map(|line| {
+        let captures = regex.captures(line)?;
+        let version = parse_version(captures.get(1)?.as_str())?;
+        Some((line.to_string(), version))
+    })
+}
+
+/// Parses a version leniently: a leading `v` or `=` is ignored and missing
+/// minor/patch components default to zero, so `v18` and `8.6` are accepted.
+pub fn parse_version(text: &str) -> Option<Version> {
+    let text = text.trim().trim_start_matches(['v', '=']).trim();
+
+    if let Ok(version) = Version::parse(text) {
+        return Some(version);
+    }
+
+    let (core, suffix) = match text.find(['-', '+']) {
+        Some(index) => text.split_at(index),
+        None => (text, ""),
+    };
+
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse::<u64>().ok()?;
+    let minor = parts.next().map(str::parse::<u64>).unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().map(str::parse::<u64>).unwrap_or(Ok(0)).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Version::parse(&format!("{}.{}.{}{}", major, minor, patch, suffix)).ok()
+}
+
+/// An npm-style version range such as `>=8.6`, `^18 || ^20`, `1.2.x` or
+/// `1.2.3 - 2.3.4`. Partial versions and `x`/`*` wildcards expand the same
+/// way they do in npm's `semver` package.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRange {
+    raw: String,
+    sets: Vec<Vec<Comparator>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    operator: Operator,
+    version: Version,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// A version whose trailing components may be missing or wildcards.
+struct PartialVersion {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: String,
+}
+
+impl VersionRange {
+    pub fn parse(text: &str) -> Result<Self> {
+        let raw = text.trim().to_string();
+        let sets = raw
+            .split("||")
+            .map(|set| Self::parse_set(set.trim()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { raw, sets })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        let version = Version {
+            build: BuildMetadata::EMPTY,
+            ..version.clone()
+        };
+
+        self.sets.iter().any(|set| {
+            set.iter().all(|comparator| comparator.matches(&version))
+                && Self::allows_pre_release(set, &version)
+        })
+    }
+
+    /// Pre-releases only satisfy a set when one of its comparators refers to
+    /// a pre-release of the same major.minor.patch, as in npm.
+    fn allows_pre_release(set: &[Comparator], version: &Version) -> bool {
+        version.pre.is_empty()
+            || set.iter().any(|comparator| {
+                !comparator.version.pre.is_empty()
+                    && comparator.version.major == version.major
+                    && comparator.version.minor == version.minor
+                    && comparator.version.patch == version.patch
+            })
+    }
+
+    fn parse_set(set: &str) -> Result<Vec<Comparator>> {
+        let tokens = Self::tokenize(set);
+
+        if tokens.len() == 3 && tokens[1] == "-" {
+            let mut comparators = Self::expand(">=", &tokens[0])?;
+            comparators.extend(Self::expand("<=", &tokens[2])?);
+            return Ok(comparators);
+        }
+
+        let mut comparators = Vec::new();
+        for token in &tokens {
+            let split = token
+                .find(|c: char| !Self::is_operator_char(c))
+                .unwrap_or(token.len());
+            let (operator, version) = token.split_at(split);
+            comparators.extend(Self::expand(operator, version)?);
+        }
+
+        Ok(comparators)
+    }
+
+    /// Splits on whitespace, re-attaching operators written as `>= 1.2`.
+    fn tokenize(set: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut pending = String::new();
+
+        for word in set.split_whitespace() {
+            if word.chars().all(Self::is_operator_char) {
+                pending.push_str(word);
+                continue;
+            }
+            tokens.push(format!("{}{}", pending, word));
+            pending.clear();
+        }
+
+        tokens
+    }
+
+    fn is_operator_char(c: char) -> bool {
+        matches!(c, '<' | '>' | '=' | '^' | '~')
+    }
+
+    fn expand(operator: &str, version: &str) -> Result<Vec<Comparator>> {
+        let partial = PartialVersion::parse(version)?;
+        let floor = partial.floor();
+
+        let Some(major) = partial.major else {
+            // `<*` and `>*` match nothing; any other wildcard matches everything.
+            return Ok(match operator {
+                "<" | ">" => vec![Comparator::new(Operator::Lt, Version::new(0, 0, 0))],
+                _ => Vec::new(),
+            });
+        };
+
+        // The first version past a partial, e.g. `1.2` -> `1.3.0`.
+        let next = match (partial.minor, partial.patch) {
+            (None, _) => Some(Version::new(major + 1, 0, 0)),
+            (Some(minor), None) => Some(Version::new(major, minor + 1, 0)),
+            _ => None,
+        };
+
+        let comparators = match (operator, next) {
+            ("" | "=", None) => vec![Comparator::new(Operator::Eq, floor)],
+            ("" | "=", Some(next)) => Comparator::between(floor, next),
+            (">", None) => vec![Comparator::new(Operator::Gt, floor)],
+            (">", Some(next)) => vec![Comparator::new(Operator::Gte, next)],
+            (">=", _) => vec![Comparator::new(Operator::Gte, floor)],
+            ("<", _) => vec![Comparator::new(Operator::Lt, floor)],
+            ("<=", None) => vec![Comparator::new(Operator::Lte, floor)],
+            ("<=", Some(next)) => vec![Comparator::new(Operator::Lt, next)],
+            ("~", _) => {
+                let ceiling = match partial.minor {
+                    Some(minor) => Version::new(major, minor + 1, 0),
+                    None => Version::new(major + 1, 0, 0),
+                };
+                Comparator::between(floor, ceiling)
+            }
+            ("^", _) => {
+                let ceiling = match (major, partial.minor, partial.patch) {
+                    (0, Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+                    (0, Some(minor), _) => Version::new(0, minor + 1, 0),
+                    _ => Version::new(major + 1, 0, 0),
+                };
+                Comparator::between(floor, ceiling)
+            }
+            _ => anyhow::bail!("Invalid version range operator '{}'", operator),
+        };
+
+        Ok(comparators)
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+impl Comparator {
+    fn new(operator: Operator, version: Version) -> Self {
+        Self { operator, version }
+    }
+
+    fn between(floor: Version, ceiling: Version) -> Vec<Self> {
+        vec![Self::new(Operator::Gte, floor), Self::new(Operator::Lt, ceiling)]
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.cmp(&self.version);
+        match self.operator {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Gte => ordering != Ordering::Less,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Lte => ordering != Ordering::Greater,
+        }
+    }
+}
+
+impl PartialVersion {
+    fn parse(text: &str) -> Result<Self> {
+        let text = text.trim().trim_start_matches(['v', '=']);
+        let invalid = || anyhow::anyhow!("Invalid version '{}' in range", text);
+
+        let without_build = text.split('+').next().unwrap_or("");
+        let (core, pre) = without_build.split_once('-').unwrap_or((without_build, ""));
+
+        let mut components = Vec::new();
+        if !core.is_empty() {
+            for part in core.split('.') {
+                match part {
+                    "x" | "X" | "*" => components.push(None),
+                    _ => components.push(Some(part.parse::<u64>().map_err(|_| invalid())?)),
+                }
+            }
+        }
+
+        if components.len() > 3 {
+            return Err(invalid());
+        }
+
+        // Anything after a wildcard is a wildcard as well.
+        if let Some(index) = components.iter().position(Option::is_none) {
+            components.truncate(index);
+        }
+        components.resize(3, None);
+
+        Ok(Self {
+            major: components[0],
+            minor: components[1],
+            patch: components[2],
+            pre: if components[2].is_some() {
+                pre.to_string()
+            } else {
+                String::new()
+            },
+        })
+    }
+
+    fn floor(&self) -> Version {
+        let mut version = Version::new(
+            self.major.unwrap_or(0),
+            self.minor.unwrap_or(0),
+            self.patch.unwrap_or(0),
+        );
+        if let Ok(pre) = Prerelease::new(&self.pre) {
+            version.pre = pre;
+        }
+        version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_plain_version() {
+        let (line, version) = extract_version("10.2.4\n", DEFAULT_VERSION_PATTERN).unwrap();
+        assert_eq!(line, "10.2.4");
+        assert_eq!(version, Version::new(10, 2, 4));
+    }
+
+    #[test]
+    fn test_extract_version_with_prefix_pre_release_and_build() {
+        let (_, version) =
+            extract_version("v1.1.39-canary.20+abc123", DEFAULT_VERSION_PATTERN).unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (1, 1, 39));
+        assert_eq!(version.pre.as_str(), "canary.20");
+        assert_eq!(version.build.as_str(), "abc123");
+    }
+
+    #[test]
+    fn test_extract_version_skips_banner_lines() {
+        let output = "npm WARN config global `--global` is deprecated\n9.8.1";
+        let (line, _) = extract_version(output, DEFAULT_VERSION_PATTERN).unwrap();
+        assert_eq!(line, "9.8.1");
+        assert!(extract_version("error: command failed", DEFAULT_VERSION_PATTERN).is_none());
+    }
+
+    #[test]
+    fn test_parse_partial_versions() {
+        assert_eq!(parse_version("v18"), Some(Version::new(18, 0, 0)));
+        assert_eq!(parse_version("8.6"), Some(Version::new(8, 6, 0)));
+        assert_eq!(parse_version("not-a-version"), None);
+    }
+
+    fn satisfies(range: &str, version: &str) -> bool {
+        VersionRange::parse(range)
+            .unwrap()
+            .matches(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_range_comparators_and_partials() {
+        assert!(satisfies(">=8.6", "8.6.0"));
+        assert!(satisfies(">= 8.6", "9.0.0"));
+        assert!(!satisfies(">=8.6", "8.5.9"));
+        assert!(satisfies(">=18.0.0 <21", "20.11.1"));
+        assert!(!satisfies(">=18.0.0 <21", "21.0.0"));
+        assert!(satisfies("1.2.x", "1.2.9"));
+        assert!(!satisfies("1.2.x", "1.3.0"));
+        assert!(satisfies("*", "0.0.1"));
+        assert!(satisfies("", "3.0.0"));
+    }
+
+    #[test]
+    fn test_range_caret_tilde_hyphen_and_union() {
+        assert!(satisfies("^1.2.3", "1.9.0"));
+        assert!(!satisfies("^1.2.3", "2.0.0"));
+        assert!(!satisfies("^0.2.3", "0.3.0"));
+        assert!(satisfies("~1.2", "1.2.7"));
+        assert!(!satisfies("~1.2", "1.3.0"));
+        assert!(satisfies("1.2.3 - 2.3", "2.3.9"));
+        assert!(!satisfies("1.2.3 - 2.3", "2.4.0"));
+        assert!(satisfies("^18 || ^20", "20.1.0"));
+        assert!(!satisfies("^18 || ^20", "19.0.0"));
+    }
+
+    #[test]
+    fn test_range_pre_release_and_build() {
+        assert!(!satisfies(">=1.0.0", "1.1.0-canary.1"));
+        assert!(satisfies(">=1.1.0-canary.0", "1.1.0-canary.1"));
+        assert!(satisfies("1.1.0", "1.1.0+abc"));
+    }
+
+    #[test]
+    fn test_range_rejects_garbage() {
+        assert!(VersionRange::parse(">=abc").is_err());
+        assert!(VersionRange::parse("1.2.3.4").is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::models::{parse_version, VersionRange};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Where a workspace's package globs come from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorkspaceSource {
+    PnpmWorkspace,
+    PackageJson,
+    /// `workspaceLayout` in nx.json, used when nothing else lists packages.
+    NxLayout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyKind {
+    Dependencies,
+    DevDependencies,
+    PeerDependencies,
+    OptionalDependencies,
+}
+
+/// A dependency of one workspace package on another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageDependency {
+    pub name: String,
+    pub range: String,
+    pub kind: DependencyKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspacePackage {
+    pub name: Option<String>,
+    /// Relative to the workspace root.
+    pub path: PathBuf,
+    pub version: Option<String>,
+    pub private: bool,
+    pub internal_dependencies: Vec<PackageDependency>,
+}
+
+/// A monorepo: its root, how packages are listed and the packages found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub name: Option<String>,
+    pub source: WorkspaceSource,
+    pub patterns: Vec<String>,
+    /// Task runners configured at the root, e.g. `turbo` and `nx`.
+    pub task_runners: Vec<String>,
+    pub packages: Vec<WorkspacePackage>,
+    pub warnings: Vec<String>,
+}
+
+impl WorkspaceSource {
+    pub fn get_display(&self) -> &'static str {
+        match self {
+            WorkspaceSource::PnpmWorkspace => "pnpm-workspace.yaml",
+            WorkspaceSource::PackageJson => "package.json workspaces",
+            WorkspaceSource::NxLayout => "nx.json workspaceLayout",
+        }
+    }
+}
+
+impl DependencyKind {
+    pub const ALL: [DependencyKind; 4] = [
+        DependencyKind::Dependencies,
+        DependencyKind::DevDependencies,
+        DependencyKind::PeerDependencies,
+        DependencyKind::OptionalDependencies,
+    ];
+
+    /// The short label shown next to a dependency; empty for runtime ones.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            DependencyKind::Dependencies => "",
+            DependencyKind::DevDependencies => "dev",
+            DependencyKind::PeerDependencies => "peer",
+            DependencyKind::OptionalDependencies => "optional",
+        }
+    }
+}
+
+impl WorkspacePackage {
+    pub fn get_display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+impl Workspace {
+    pub fn new(root: PathBuf, source: WorkspaceSource, patterns: Vec<String>) -> Self {
+        Self {
+            root,
+            name: None,
+            source,
+            patterns,
+            task_runners: Vec::new(),
+            packages: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn find_package(&self, name: &str) -> Option<&WorkspacePackage> {
+        self.packages.iter().find(|p| p.name.as_deref() == Some(name))
+    }
+
+    /// Packages that depend on `name`, in listing order.
+    #[cfg(test)]
+    pub fn get_dependents(&self, name: &str) -> Vec<&WorkspacePackage> {
+        self.packages
+            .iter()
+            .filter(|p| p.internal_dependencies.iter().any(|d| d.name == name))
+            .collect()
+    }
+
+    /// Adds the packages, keeping from each one's declared dependencies only
+    /// those on other workspace packages, and warns about duplicate names and
+    /// ranges the workspace version does not satisfy.
+    pub fn add_packages(&mut self, packages: Vec<(WorkspacePackage, Vec<PackageDependency>)>) {
+        let mut names: Vec<&str> = Vec::new();
+        for (package, _) in &packages {
+            if let Some(name) = package.name.as_deref() {
+                if names.contains(&name) {
+                    self.warnings.push(format!("More than one workspace package is named {}", name));
+                } else {
+                    names.push(name);
+                }
+            }
+        }
+
+        let versions: Vec<(String, Option<String>)> = packages
+            .iter()
+            .filter_map(|(p, _)| p.name.clone().map(|name| (name, p.version.clone())))
+            .collect();
+        for (mut package, dependencies) in packages {
+            for dependency in dependencies {
+                let Some((_, version)) = versions.iter().find(|(name, _)| *name == dependency.name) else {
+                    continue;
+                };
+                if !is_satisfied_locally(&dependency.range, version.as_deref()) {
+                    self.warnings.push(format!(
+                        "{} wants {}@{} but the workspace has {}; it will come from the registry",
+                        package.get_display_name(),
+                        dependency.name,
+                        dependency.range,
+                        version.as_deref().unwrap_or("no version")
+                    ));
+                }
+                package.internal_dependencies.push(dependency);
+            }
+            self.packages.push(package);
+        }
+    }
+}
+
+/// Whether a dependency range links the local package rather than fetching
+/// it, with npm's range semantics. `workspace:`, `file:`/`link:` paths and
+/// unparsable ranges are taken as local.
+fn is_satisfied_locally(range: &str, version: Option<&str>) -> bool {
+    if range.starts_with("workspace:") || range.starts_with("file:") || range.starts_with("link:") {
+        return true;
+    }
+    let (Ok(requirement), Some(Some(version))) = (VersionRange::parse(range), version.map(parse_version)) else {
+        return true;
+    };
+    requirement.matches(&version)
+}
+
+/// Matches a workspace glob such as `packages/*` or `apps/**` against a
+/// `/`-separated path relative to the root. `*` and `?` stay within one
+/// segment; `**` spans any number of segments.
+pub fn matches_workspace_pattern(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    matches_segments(&pattern, &path)
+}
+
+fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            matches_segments(&pattern[1..], path) || (!path.is_empty() && matches_segments(pattern, &path[1..]))
+        }
+        (Some(segment), Some(name)) => {
+            matches_segment(segment.as_bytes(), name.as_bytes()) && matches_segments(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn matches_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => matches_segment(&pattern[1..], name) || (!name.is_empty() && matches_segment(pattern, &name[1..])),
+        (Some(b'?'), Some(_)) => matches_segment(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => matches_segment(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_workspace_pattern() {
+        assert!(matches_workspace_pattern("packages/*", "packages/ui"));
+        assert!(!matches_workspace_pattern("packages/*", "packages/ui/src"));
+        assert!(matches_workspace_pattern("./apps/**", "apps/web/admin"));
+        assert!(matches_workspace_pattern("apps/**", "apps"));
+        assert!(matches_workspace_pattern("tools/cli-?", "tools/cli-a"));
+        assert!(!matches_workspace_pattern("apps/*-app", "apps/web"));
+    }
+
+    #[test]
+    fn test_is_satisfied_locally_uses_npm_ranges() {
+        assert!(is_satisfied_locally("1.2.3", Some("1.2.3")));
+        assert!(!is_satisfied_locally("1.2.3", Some("1.2.4")));
+        assert!(is_satisfied_locally("^1 || ^2", Some("2.5.0")));
+        assert!(!is_satisfied_locally("^1 || ^2", Some("3.0.0")));
+        assert!(is_satisfied_locally("1.x", Some("1.9.0")));
+        assert!(!is_satisfied_locally("1.x", Some("2.0.0")));
+        assert!(is_satisfied_locally("workspace:^", Some("0.1.0")));
+        assert!(is_satisfied_locally("^1.0.0", None));
+    }
+
+    #[test]
+    fn test_add_packages_keeps_internal_dependencies() {
+        let package = |name: &str, version: &str| WorkspacePackage {
+            name: Some(name.to_string()),
+            path: PathBuf::from("packages").join(name),
+            version: Some(version.to_string()),
+            private: false,
+            internal_dependencies: Vec::new(),
+        };
+        let dependency = |name: &str, range: &str| PackageDependency {
+            name: name.to_string(),
+            range: range.to_string(),
+            kind: DependencyKind::Dependencies,
+        };
+
+        let mut workspace = Workspace::new(PathBuf::from("/repo"), WorkspaceSource::PackageJson, Vec::new());
+        workspace.add_packages(vec![
+            (package("ui", "1.2.0"), vec![dependency("react", "^18.0.0")]),
+            (package("web", "0.1.0"), vec![dependency("ui", "^1.0.0"), dependency("react", "^18.0.0")]),
+            (package("docs", "0.1.0"), vec![dependency("ui", "^2.0.0")]),
+        ]);
+
+        assert!(workspace.find_package("ui").unwrap().internal_dependencies.is_empty());
+        assert_eq!(workspace.find_package("web").unwrap().internal_dependencies.len(), 1);
+        assert_eq!(workspace.get_dependents("ui").len(), 2);
+        assert_eq!(workspace.warnings, vec!["docs wants ui@^2.0.0 but the workspace has 1.2.0; it will come from the registry"]);
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The settings fazrepo reads from a Yarn Berry `.yarnrc.yml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YarnRc {
+    #[serde(skip)]
+    pub path: PathBuf,
+    #[serde(default)]
+    pub yarn_path: Option<String>,
+    #[serde(default)]
+    pub node_linker: Option<String>,
+    #[serde(default)]
+    pub npm_registry_server: Option<String>,
+    #[serde(default)]
+    pub npm_auth_token: Option<String>,
+    #[serde(default)]
+    pub npm_auth_ident: Option<String>,
+    /// Keyed by scope name without the `@`.
+    #[serde(default)]
+    pub npm_scopes: HashMap<String, YarnNpmSettings>,
+    /// Per-registry auth, keyed by registry URL (e.g. `//npm.example.com`).
+    #[serde(default)]
+    pub npm_registries: HashMap<String, YarnNpmSettings>,
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YarnNpmSettings {
+    #[serde(default)]
+    pub npm_registry_server: Option<String>,
+    #[serde(default)]
+    pub npm_auth_token: Option<String>,
+    #[serde(default)]
+    pub npm_auth_ident: Option<String>,
+}
+
+impl YarnNpmSettings {
+    pub fn get_auth(&self) -> Option<&str> {
+        self.npm_auth_token.as_deref().or(self.npm_auth_ident.as_deref())
+    }
+}
+
+/// A Yarn Classic `.yarnrc`, made of `key value` lines.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct YarnClassicRc {
+    pub path: PathBuf,
+    pub entries: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum YarnFlavor {
+    Classic,
+    Berry,
+}
+
+/// How Yarn behaves in the current project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YarnDetails {
+    pub flavor: Option<YarnFlavor>,
+    pub linker: Option<String>,
+    pub yarnrc: Option<PathBuf>,
+    pub yarn_path: Option<PathBuf>,
+    pub releases: Vec<PathBuf>,
+    pub global_version: Option<String>,
+    pub global_path: Option<PathBuf>,
+}
+
+impl YarnRc {
+    /// `yarnPath` resolved against the directory of the `.yarnrc.yml`.
+    pub fn get_yarn_path(&self) -> Option<PathBuf> {
+        let yarn_path = self.yarn_path.as_ref()?;
+        let base = self.path.parent().unwrap_or(&self.path);
+        Some(base.join(yarn_path))
+    }
+}
+
+impl YarnClassicRc {
+    pub fn parse(path: PathBuf, content: &str) -> Self {
+        let entries = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (key, value) = if let Some(rest) = line.strip_prefix('"') {
+                    let (key, value) = rest.split_once('"')?;
+                    (key, value)
+                } else {
+                    line.split_once(char::is_whitespace)?
+                };
+                Some((key.trim().to_string(), value.trim().trim_matches('"').to_string()))
+            })
+            .collect();
+
+        Self { path, entries }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl YarnFlavor {
+    pub fn from_major(major: u64) -> Self {
+        if major >= 2 {
+            YarnFlavor::Berry
+        } else {
+            YarnFlavor::Classic
+        }
+    }
+
+    pub fn get_display_name(&self) -> &'static str {
+        match self {
+            YarnFlavor::Classic => "Yarn Classic",
+            YarnFlavor::Berry => "Yarn Berry",
+        }
+    }
+
+    /// The linker used when `.yarnrc.yml` does not set `nodeLinker`.
+    pub fn get_default_linker(&self) -> &'static str {
+        match self {
+            YarnFlavor::Classic => "node-modules",
+            YarnFlavor::Berry => "pnp",
+        }
+    }
+}
+
+/// Expands `${NAME}`, `${NAME:-fallback}` and `${NAME-fallback}` the way
+/// Yarn Berry does for `.yarnrc.yml` values.
+pub fn expand_yarn_env(value: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let reference = &rest[start + 2..start + end];
+        let value = match reference.split_once(":-") {
+            Some((name, fallback)) => std::env::var(name)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| fallback.to_string()),
+            None => match reference.split_once('-') {
+                Some((name, fallback)) => std::env::var(name).unwrap_or_else(|_| fallback.to_string()),
+                None => std::env::var(reference).unwrap_or_default(),
+            },
+        };
+        expanded.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
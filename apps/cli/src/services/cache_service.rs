@@ -0,0 +1,143 @@
+use crate::models::{CacheEntry, CacheKey, PackageManagerCheckResult};
+use crate::utils::constants::{APP_NAME, DEFAULT_CACHE_TTL_SECS};
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub trait CacheService {
+    fn get(&self, key: &CacheKey) -> Option<PackageManagerCheckResult>;
+    fn put(&self, key: CacheKey, result: &PackageManagerCheckResult);
+    fn clear(&self) -> Result<usize>;
+    fn get_cache_dir(&self) -> PathBuf;
+}
+
+/// Stores each cached check result as its own JSON file, so concurrent
+/// probes never write to the same file.
+#[derive(Clone)]
+pub struct DefaultCacheService {
+    directory: PathBuf,
+    ttl: Duration,
+}
+
+impl DefaultCacheService {
+    pub fn new() -> Self {
+        let directory = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join(APP_NAME)
+            .join("checks");
+
+        Self {
+            directory,
+            ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_directory(mut self, directory: PathBuf) -> Self {
+        self.directory = directory;
+        self
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+impl CacheService for DefaultCacheService {
+    fn get(&self, key: &CacheKey) -> Option<PackageManagerCheckResult> {
+        let content = fs::read_to_string(self.directory.join(key.get_file_name())).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+
+        if &entry.key != key || Self::now().saturating_sub(entry.cached_at) >= self.ttl.as_secs() {
+            return None;
+        }
+
+        Some(entry.result.mark_cached())
+    }
+
+    fn put(&self, key: CacheKey, result: &PackageManagerCheckResult) {
+        // The cache is an optimisation; failing to write it must not fail a check.
+        let path = self.directory.join(key.get_file_name());
+        let entry = CacheEntry {
+            key,
+            cached_at: Self::now(),
+            result: result.clone(),
+        };
+        if let Ok(content) = serde_json::to_string(&entry) {
+            let _ = fs::create_dir_all(&self.directory).and_then(|_| fs::write(path, content));
+        }
+    }
+
+    fn clear(&self) -> Result<usize> {
+        if !self.directory.is_dir() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn get_cache_dir(&self) -> PathBuf {
+        self.directory.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PackageManager;
+
+    fn key(dir: &std::path::Path) -> CacheKey {
+        let binary = dir.join("pnpm");
+        fs::write(&binary, "").unwrap();
+        CacheKey::new(&PackageManager::new("pnpm", "Performant npm", "", "pnpm"), &binary).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DefaultCacheService::new().with_directory(dir.path().join("cache"));
+        let key = key(dir.path());
+        let result = PackageManagerCheckResult::success(
+            PackageManager::new("pnpm", "Performant npm", "", "pnpm").with_version("8.15.1".to_string()),
+        );
+
+        assert!(cache.get(&key).is_none());
+        cache.put(key.clone(), &result);
+
+        let cached = cache.get(&key).unwrap();
+        assert!(cached.from_cache);
+        assert_eq!(cached.package_manager.version.as_deref(), Some("8.15.1"));
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DefaultCacheService::new()
+            .with_directory(dir.path().join("cache"))
+            .with_ttl(Duration::ZERO);
+        let key = key(dir.path());
+
+        cache.put(key.clone(), &PackageManagerCheckResult::success(PackageManager::new("pnpm", "", "", "pnpm")));
+        assert!(cache.get(&key).is_none());
+    }
+}
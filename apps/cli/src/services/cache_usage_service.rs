@@ -0,0 +1,211 @@
+use crate::models::PackageManagerCheckResult;
+use crate::utils::constants::DEFAULT_PROBE_TIMEOUT_MS;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+#[async_trait::async_trait]
+pub trait CacheUsageService {
+    /// Asks the manager where its cache or store lives.
+    async fn locate(&self, result: &PackageManagerCheckResult) -> Result<PathBuf>;
+    /// Bytes on disk under `directory`, counting hard-linked files once.
+    fn measure(&self, directory: &Path) -> Result<u64>;
+    /// Runs the manager's own cleanup command.
+    async fn prune(&self, result: &PackageManagerCheckResult) -> Result<()>;
+}
+
+#[derive(Clone)]
+pub struct DefaultCacheUsageService {
+    timeout: Duration,
+}
+
+impl DefaultCacheUsageService {
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_millis(DEFAULT_PROBE_TIMEOUT_MS),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn is_yarn_berry(&self, result: &PackageManagerCheckResult) -> bool {
+        result.package_manager.name == "yarn"
+            && result.package_manager.parsed_version.as_ref().is_some_and(|v| v.major >= 2)
+    }
+
+    /// Yarn Berry has no `cache dir`; its cache is global unless
+    /// `enableGlobalCache` is turned off for the project.
+    async fn locate_yarn_berry(&self, binary: &Path) -> Result<PathBuf> {
+        let global = self.run(binary, &["config", "get", "enableGlobalCache"], true).await?;
+        if global == "true" {
+            let folder = self.run(binary, &["config", "get", "globalFolder"], true).await?;
+            Ok(PathBuf::from(folder).join("cache"))
+        } else {
+            Ok(PathBuf::from(self.run(binary, &["config", "get", "cacheFolder"], true).await?))
+        }
+    }
+
+    /// The last line of stdout; some managers print notices before the path.
+    /// Runs from the home directory unless `in_project`, as npm refuses
+    /// `config get` inside a workspace.
+    async fn run(&self, binary: &Path, args: &[&str], in_project: bool) -> Result<String> {
+        let mut cmd = Command::new(binary);
+        cmd.args(args).stdin(Stdio::null()).kill_on_drop(true);
+        if let Some(home) = dirs::home_dir().filter(|_| !in_project) {
+            cmd.current_dir(home);
+        }
+
+        let output = match tokio::time::timeout(self.timeout, cmd.output()).await {
+            Ok(output) => output?,
+            Err(_) => anyhow::bail!("Timed out after {:.1}s", self.timeout.as_secs_f64()),
+        };
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("Command failed");
+            anyhow::bail!("{}", message.trim());
+        }
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .map(str::to_string)
+            .context("Command printed nothing")
+    }
+
+    fn get_binary<'a>(&self, result: &'a PackageManagerCheckResult) -> Result<&'a Path> {
+        result
+            .package_manager
+            .path
+            .as_deref()
+            .with_context(|| format!("{} is not installed", result.package_manager.name))
+    }
+
+    fn add_usage(&self, path: &Path, seen: &mut HashSet<(u64, u64)>) -> Result<u64> {
+        let metadata = fs::symlink_metadata(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        let mut size = if is_first_link(&metadata, seen) {
+            disk_size(&metadata)
+        } else {
+            0
+        };
+        if metadata.is_dir() {
+            for entry in fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))? {
+                size += self.add_usage(&entry?.path(), seen)?;
+            }
+        }
+        Ok(size)
+    }
+}
+
+#[cfg(unix)]
+fn disk_size(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn disk_size(metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+/// pnpm's store and npm's cache hard-link files, which only take space once.
+#[cfg(unix)]
+fn is_first_link(metadata: &fs::Metadata, seen: &mut HashSet<(u64, u64)>) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink() <= 1 || seen.insert((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn is_first_link(_metadata: &fs::Metadata, _seen: &mut HashSet<(u64, u64)>) -> bool {
+    true
+}
+
+#[async_trait::async_trait]
+impl CacheUsageService for DefaultCacheUsageService {
+    async fn locate(&self, result: &PackageManagerCheckResult) -> Result<PathBuf> {
+        let binary = self.get_binary(result)?;
+        if self.is_yarn_berry(result) {
+            return self.locate_yarn_berry(binary).await;
+        }
+
+        let commands = result
+            .package_manager
+            .cache_commands
+            .as_ref()
+            .with_context(|| format!("{} has no cache", result.package_manager.name))?;
+        let args: Vec<&str> = commands.locate.iter().map(String::as_str).collect();
+        Ok(PathBuf::from(self.run(binary, &args, false).await?))
+    }
+
+    fn measure(&self, directory: &Path) -> Result<u64> {
+        if !directory.exists() {
+            return Ok(0);
+        }
+        self.add_usage(directory, &mut HashSet::new())
+    }
+
+    async fn prune(&self, result: &PackageManagerCheckResult) -> Result<()> {
+        let binary = self.get_binary(result)?;
+        let in_project = self.is_yarn_berry(result);
+        let args: Vec<&str> = if in_project {
+            vec!["cache", "clean", "--all"]
+        } else {
+            let commands = result
+                .package_manager
+                .cache_commands
+                .as_ref()
+                .with_context(|| format!("{} has no cache", result.package_manager.name))?;
+            commands.prune.iter().map(String::as_str).collect()
+        };
+
+        // Cleaning a large cache can take far longer than a version probe,
+        // so this is not bound by the probe timeout.
+        let mut cmd = Command::new(binary);
+        cmd.args(&args).stdin(Stdio::null());
+        if let Some(home) = dirs::home_dir().filter(|_| !in_project) {
+            cmd.current_dir(home);
+        }
+        let output = cmd
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {} {}", result.package_manager.name, args.join(" ")))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("Cleanup failed");
+            anyhow::bail!("{} {}: {}", result.package_manager.name, args.join(" "), message.trim());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_counts_hard_links_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("store");
+        fs::create_dir_all(store.join("nested")).unwrap();
+        fs::write(store.join("a"), vec![1u8; 64 * 1024]).unwrap();
+        fs::write(store.join("nested").join("b"), vec![2u8; 64 * 1024]).unwrap();
+
+        let service = DefaultCacheUsageService::new();
+        let size = service.measure(&store).unwrap();
+        assert!(size >= 128 * 1024);
+
+        fs::hard_link(store.join("a"), store.join("nested").join("a-link")).unwrap();
+        assert_eq!(service.measure(&store).unwrap(), size);
+        assert_eq!(service.measure(&dir.path().join("missing")).unwrap(), 0);
+    }
+}
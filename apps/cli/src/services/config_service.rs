@@ -1,4 +1,5 @@
 use crate::models::AppConfig;
+use crate::utils::constants::TEMPLATE_DIRECTORY;
 use anyhow::Result;
 use serde_json;
 use std::fs;
@@ -33,7 +34,8 @@ impl ConfigService for DefaultConfigService {
         }
 
         let content = fs::read_to_string(&config_path)?;
-        let config: AppConfig = serde_json::from_str(&content)?;
+        let mut config: AppConfig = serde_json::from_str(&content)?;
+        config.remove_invalid_entries();
         Ok(config)
     }
 
@@ -47,11 +49,11 @@ impl ConfigService for DefaultConfigService {
     fn init_config(&self) -> Result<AppConfig> {
         let mut config = AppConfig::new();
         config.initialized = true;
-        
-        // Add default templates
+
+        // Add default templates; each one replaces the built-in once its directory exists
         config = config
-            .with_template("fullstack-nextjs", "Full-stack Next.js application")
-            .with_template("api-express", "Express.js API with TypeScript");
+            .with_template("fullstack-nextjs", &format!("{}/fullstack-nextjs", TEMPLATE_DIRECTORY))
+            .with_template("api-express", &format!("{}/api-express", TEMPLATE_DIRECTORY));
 
         self.save_config(&config)?;
         Ok(config)
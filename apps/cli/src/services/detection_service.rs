@@ -0,0 +1,142 @@
+use crate::models::{DetectionSource, Lockfile, ProjectDetection};
+use crate::services::{DefaultManifestService, ManifestService};
+use crate::utils::constants::LOCKFILES;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+pub trait DetectionService {
+    fn detect(&self, directory: &Path) -> Result<ProjectDetection>;
+}
+
+#[derive(Clone)]
+pub struct DefaultDetectionService {
+    manifest_service: DefaultManifestService,
+}
+
+impl DefaultDetectionService {
+    pub fn new() -> Self {
+        Self {
+            manifest_service: DefaultManifestService::new(),
+        }
+    }
+
+    fn find_lockfiles(&self, directory: &Path) -> Vec<Lockfile> {
+        LOCKFILES
+            .iter()
+            .map(|(file, manager)| (directory.join(file), manager))
+            .filter(|(path, _)| path.is_file())
+            .map(|(path, manager)| Lockfile {
+                manager: manager.to_string(),
+                path,
+            })
+            .collect()
+    }
+
+    /// Lockfiles live at the workspace root, which may be above the
+    /// nearest package.json, so the root is the closest ancestor that has one.
+    fn find_project_root(&self, directory: &Path, package_json: Option<&Path>) -> PathBuf {
+        directory
+            .ancestors()
+            .find(|dir| !self.find_lockfiles(dir).is_empty())
+            .or_else(|| package_json.and_then(Path::parent))
+            .unwrap_or(directory)
+            .to_path_buf()
+    }
+}
+
+impl DetectionService for DefaultDetectionService {
+    fn detect(&self, directory: &Path) -> Result<ProjectDetection> {
+        let package_json = self.manifest_service.find_package_json(directory)?;
+        let root = self.find_project_root(directory, package_json.as_ref().map(|p| p.path.as_path()));
+
+        let mut detection = ProjectDetection::new(root.clone());
+        detection.lockfiles = self.find_lockfiles(&root);
+        detection.pin = package_json.as_ref().and_then(|p| p.get_pin());
+
+        // A nested package usually leaves the pin to the workspace root.
+        let root_manifest = root.join("package.json");
+        if detection.pin.is_none() && root_manifest.is_file() {
+            detection.pin = self.manifest_service.read_package_json(&root_manifest)?.get_pin();
+        }
+
+        let lockfile_managers: Vec<String> = detection
+            .get_lockfile_managers()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        if lockfile_managers.len() > 1 {
+            let files: Vec<String> = detection
+                .lockfiles
+                .iter()
+                .map(|l| format!("{} ({})", l.path.file_name().unwrap_or_default().to_string_lossy(), l.manager))
+                .collect();
+            detection.add_warning(format!("Conflicting lockfiles: {}", files.join(", ")));
+        }
+
+        if let Some(pin) = detection.pin.clone() {
+            if !lockfile_managers.is_empty() && !lockfile_managers.contains(&pin.name) {
+                detection.add_warning(format!(
+                    "packageManager pins {} but the lockfiles belong to {}",
+                    pin.name,
+                    lockfile_managers.join(", ")
+                ));
+            }
+            detection.manager = Some(pin.name);
+            detection.source = Some(DetectionSource::PackageManagerField);
+        } else if lockfile_managers.len() == 1 {
+            detection.manager = Some(lockfile_managers[0].clone());
+            detection.source = Some(DetectionSource::Lockfile);
+        }
+
+        Ok(detection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_detects_from_lockfile() {
+        let dir = project(&[("package.json", "{}"), ("pnpm-lock.yaml", "")]);
+        let detection = DefaultDetectionService::new().detect(dir.path()).unwrap();
+        assert_eq!(detection.manager.as_deref(), Some("pnpm"));
+        assert_eq!(detection.source, Some(DetectionSource::Lockfile));
+        assert!(detection.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_package_manager_field_wins_and_conflicts_warn() {
+        let dir = project(&[
+            ("package.json", r#"{"packageManager":"yarn@4.1.0"}"#),
+            ("package-lock.json", "{}"),
+            ("bun.lockb", ""),
+        ]);
+        let detection = DefaultDetectionService::new().detect(dir.path()).unwrap();
+        assert_eq!(detection.manager.as_deref(), Some("yarn"));
+        assert_eq!(detection.source, Some(DetectionSource::PackageManagerField));
+        assert_eq!(detection.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_subdirectory_uses_workspace_root_lockfile() {
+        let dir = project(&[("package.json", "{}"), ("yarn.lock", "")]);
+        let sub = dir.path().join("packages").join("app");
+        fs::create_dir_all(&sub).unwrap();
+        fs::write(sub.join("package.json"), "{}").unwrap();
+
+        let detection = DefaultDetectionService::new().detect(&sub).unwrap();
+        assert_eq!(detection.directory, dir.path());
+        assert_eq!(detection.manager.as_deref(), Some("yarn"));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::models::{parse_global_packages, GlobalListing, PackageManagerCheckResult};
+use crate::utils::constants::DEFAULT_PROBE_TIMEOUT_MS;
+use anyhow::Result;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+#[async_trait::async_trait]
+pub trait GlobalsService {
+    /// Lists the packages installed globally through an installed manager.
+    async fn list_globals(&self, result: &PackageManagerCheckResult) -> GlobalListing;
+}
+
+#[derive(Clone)]
+pub struct DefaultGlobalsService {
+    timeout: Duration,
+}
+
+impl DefaultGlobalsService {
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_millis(DEFAULT_PROBE_TIMEOUT_MS),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn get_list_args(&self, manager: &str) -> Option<&'static [&'static str]> {
+        match manager {
+            "npm" => Some(&["ls", "-g", "--json", "--depth=0"]),
+            "pnpm" => Some(&["ls", "-g", "--json"]),
+            "yarn" => Some(&["global", "list"]),
+            "bun" => Some(&["pm", "ls", "-g"]),
+            _ => None,
+        }
+    }
+
+    /// npm exits non-zero when the global tree has problems but still
+    /// prints it, so stdout is used whenever there is any.
+    async fn run(&self, binary: &Path, args: &[&str]) -> Result<String> {
+        let mut cmd = Command::new(binary);
+        cmd.args(args).stdin(Stdio::null()).kill_on_drop(true);
+        // Global lists must not pick up a project's yarnPath or packageManager pin.
+        if let Some(home) = dirs::home_dir() {
+            cmd.current_dir(home);
+        }
+
+        let output = match tokio::time::timeout(self.timeout, cmd.output()).await {
+            Ok(output) => output?,
+            Err(_) => anyhow::bail!("Timed out after {:.1}s", self.timeout.as_secs_f64()),
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() || !stdout.is_empty() {
+            Ok(stdout)
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().next().unwrap_or("Failed to list global packages");
+            Err(anyhow::anyhow!(message.trim().to_string()))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl GlobalsService for DefaultGlobalsService {
+    async fn list_globals(&self, result: &PackageManagerCheckResult) -> GlobalListing {
+        let pm = &result.package_manager;
+        let version = pm.parsed_version.as_ref().map(|v| v.to_string());
+
+        let Some(args) = self.get_list_args(&pm.name) else {
+            return GlobalListing::failed(&pm.name, version, "Global packages are not supported".to_string());
+        };
+        if !result.success {
+            let error = result.error_message.clone().unwrap_or_else(|| "Version check failed".to_string());
+            return GlobalListing::failed(&pm.name, version, error);
+        }
+        if pm.name == "yarn" && pm.parsed_version.as_ref().is_some_and(|v| v.major >= 2) {
+            return GlobalListing::failed(&pm.name, version, "Yarn Berry has no global packages".to_string());
+        }
+        let Some(path) = pm.path.as_ref() else {
+            return GlobalListing::failed(&pm.name, version, "Not installed".to_string());
+        };
+
+        match self
+            .run(path, args)
+            .await
+            .and_then(|output| parse_global_packages(&pm.name, &output))
+        {
+            Ok(packages) => GlobalListing::new(&pm.name, version, packages),
+            Err(e) => GlobalListing::failed(&pm.name, version, e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::models::PackageJson;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+pub trait ManifestService {
+    fn find_package_json(&self, start: &Path) -> Result<Option<PackageJson>>;
+    fn read_package_json(&self, path: &Path) -> Result<PackageJson>;
+}
+
+#[derive(Clone)]
+pub struct DefaultManifestService;
+
+impl DefaultManifestService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ManifestService for DefaultManifestService {
+    fn find_package_json(&self, start: &Path) -> Result<Option<PackageJson>> {
+        for dir in start.ancestors() {
+            let candidate = dir.join("package.json");
+            if candidate.is_file() {
+                return self.read_package_json(&candidate).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn read_package_json(&self, path: &Path) -> Result<PackageJson> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut package_json: PackageJson = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        package_json.path = path.to_path_buf();
+        Ok(package_json)
+    }
+}
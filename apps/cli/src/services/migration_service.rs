@@ -0,0 +1,713 @@
+use crate::models::{
+    rewrite_shell, Dialect, FileChange, ManagerAction, MigrationPlan, MigrationTarget, ProjectDetection,
+};
+use crate::services::{DefaultWorkspaceService, WorkspaceService};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// CI configuration files outside `.github/workflows` whose commands are rewritten.
+const CI_FILES: &[&str] = &[
+    ".gitlab-ci.yml",
+    ".circleci/config.yml",
+    "azure-pipelines.yml",
+    "bitbucket-pipelines.yml",
+];
+const PNPM_WORKSPACE_FILE: &str = "pnpm-workspace.yaml";
+/// CI keys whose value, or block of lines, is a shell command.
+const CI_COMMAND_KEYS: &[&str] = &["run", "script", "before_script", "after_script", "command"];
+
+pub trait MigrationService {
+    /// Works out every file change that moves the project in `detection` to
+    /// `target`, without touching the disk.
+    fn plan(&self, detection: &ProjectDetection, target: &MigrationTarget) -> Result<MigrationPlan>;
+    /// Backs up and writes the files of `plan`, in order. When one change
+    /// fails, the ones already made are undone.
+    fn apply(&self, plan: &MigrationPlan) -> Result<()>;
+}
+
+#[derive(Clone)]
+pub struct DefaultMigrationService {
+    workspace_service: DefaultWorkspaceService,
+}
+
+impl DefaultMigrationService {
+    pub fn new() -> Self {
+        Self {
+            workspace_service: DefaultWorkspaceService::new(),
+        }
+    }
+
+    /// Invocations of every other manager are rewritten. Bun is only
+    /// rewritten when leaving it, as `bun` is also used as a runtime.
+    fn get_rewritten_programs(&self, from: Option<&str>, target: &str) -> Vec<&'static str> {
+        let mut programs: Vec<&str> = ["npm", "yarn", "pnpm"].into_iter().filter(|p| *p != target).collect();
+        if from == Some("bun") && target != "bun" {
+            programs.push("bun");
+        }
+        programs
+    }
+
+    fn plan_package_json(
+        &self,
+        package: &mut serde_json::Map<String, Value>,
+        detection: &ProjectDetection,
+        target: &MigrationTarget,
+        programs: &[&str],
+        changes: &mut Vec<FileChange>,
+        notes: &mut Vec<String>,
+    ) -> Result<()> {
+        let from = detection.manager.as_deref();
+        // Corepack only knows npm, yarn and pnpm.
+        if target.manager == "bun" {
+            package.shift_remove("packageManager");
+        } else {
+            package.insert(
+                "packageManager".to_string(),
+                Value::String(format!("{}@{}", target.manager, target.version)),
+            );
+        }
+
+        rewrite_scripts(package, "package.json", target, programs, notes);
+
+        let workspace_path = detection.directory.join(PNPM_WORKSPACE_FILE);
+        if target.manager == "pnpm" {
+            if let Some(workspaces) = package.shift_remove("workspaces") {
+                let patterns = get_workspace_patterns(&workspaces, notes);
+                if workspace_path.exists() {
+                    notes.push(format!(
+                        "{} already exists; make sure it lists {}",
+                        PNPM_WORKSPACE_FILE,
+                        patterns.join(", ")
+                    ));
+                } else {
+                    changes.push(FileChange::Write {
+                        path: workspace_path,
+                        before: None,
+                        after: format_pnpm_workspace(&patterns),
+                    });
+                }
+            }
+        } else if workspace_path.is_file() {
+            let content = fs::read_to_string(&workspace_path)
+                .with_context(|| format!("Failed to read {}", workspace_path.display()))?;
+            let yaml: serde_yaml::Value = serde_yaml::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", workspace_path.display()))?;
+
+            let patterns: Vec<String> = yaml
+                .get("packages")
+                .and_then(|p| p.as_sequence())
+                .map(|p| p.iter().filter_map(|p| p.as_str().map(str::to_string)).collect())
+                .unwrap_or_default();
+            let other_keys: Vec<&str> = yaml
+                .as_mapping()
+                .map(|m| m.keys().filter_map(|k| k.as_str()).filter(|k| *k != "packages").collect())
+                .unwrap_or_default();
+            let backup = get_backup_path(&workspace_path, changes);
+            if !other_keys.is_empty() {
+                notes.push(format!(
+                    "{} also sets {}, which only pnpm reads; port them by hand from {}",
+                    PNPM_WORKSPACE_FILE,
+                    other_keys.join(", "),
+                    backup.file_name().unwrap_or_default().to_string_lossy()
+                ));
+            }
+            if target.manager == "npm" && patterns.iter().any(|p| p.starts_with('!')) {
+                notes.push("npm workspaces do not support negated (`!`) patterns".to_string());
+            }
+            if !package.contains_key("workspaces") && !patterns.is_empty() {
+                package.insert("workspaces".to_string(), Value::from(patterns));
+            }
+            changes.push(FileChange::Backup {
+                path: workspace_path,
+                backup,
+            });
+        }
+
+        if target.manager == "npm" && package.contains_key("resolutions") {
+            notes.push("npm ignores `resolutions` in package.json; move them to `overrides`".to_string());
+        }
+        if from == Some("pnpm") && target.manager != "pnpm" && package.contains_key("pnpm") {
+            notes.push("the `pnpm` section of package.json only applies to pnpm".to_string());
+        }
+        let uses_workspace_protocol = ["dependencies", "devDependencies", "peerDependencies", "optionalDependencies"]
+            .iter()
+            .filter_map(|field| package.get(*field).and_then(Value::as_object))
+            .flat_map(|deps| deps.values())
+            .any(|version| version.as_str().is_some_and(|v| v.starts_with("workspace:")));
+        if target.manager == "npm" && uses_workspace_protocol {
+            notes.push("npm does not support `workspace:` versions; replace them with the packages' versions".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the scripts of every workspace package below the project.
+    fn plan_workspace_packages(
+        &self,
+        directory: &Path,
+        target: &MigrationTarget,
+        programs: &[&str],
+        changes: &mut Vec<FileChange>,
+        notes: &mut Vec<String>,
+    ) -> Result<()> {
+        let Some(workspace) = self.workspace_service.discover(directory)? else {
+            return Ok(());
+        };
+        if workspace.root != directory {
+            return Ok(());
+        }
+
+        for package in &workspace.packages {
+            let path = directory.join(&package.path).join("package.json");
+            if package.path.as_os_str().is_empty() || !path.is_file() {
+                continue;
+            }
+            let name = path.strip_prefix(directory).unwrap_or(&path).display().to_string();
+            let before = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let original: Value =
+                serde_json::from_str(&before).with_context(|| format!("Failed to parse {}", path.display()))?;
+            let mut manifest = original.clone();
+            if let Some(fields) = manifest.as_object_mut() {
+                rewrite_scripts(fields, &name, target, programs, notes);
+            }
+            if manifest != original {
+                changes.push(FileChange::Write {
+                    after: format_json_like(&manifest, &before)?,
+                    path,
+                    before: Some(before),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn find_ci_files(&self, directory: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(directory.join(".github").join("workflows"))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "yml" || ext == "yaml"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files.extend(CI_FILES.iter().map(|file| directory.join(file)).filter(|path| path.is_file()));
+        files
+    }
+
+    /// Rewrites the commands of `run:` and `script:` style steps: an inline
+    /// value, the lines of a `|` or `>` block, or the items of a list.
+    /// Everything else, names and environment values included, is kept.
+    fn rewrite_ci_file(
+        &self,
+        content: &str,
+        is_github: bool,
+        target: &MigrationTarget,
+        programs: &[&str],
+        notes: &mut Vec<String>,
+    ) -> String {
+        let command_key = Regex::new(&format!(
+            r"^(\s*(?:-\s+)?)((?:{}):)(\s*)(.*)$",
+            CI_COMMAND_KEYS.join("|")
+        ))
+        .unwrap();
+        let list_item = Regex::new(r"^(\s*-\s+)(.*)$").unwrap();
+        let cache_line = Regex::new(r#"^(\s*cache:\s*['"]?)(npm|yarn|pnpm)(['"]?\s*)$"#).unwrap();
+
+        let rewrite = |command: &str, notes: &mut Vec<String>| {
+            let (shell, shell_notes) = rewrite_shell(command, programs, target.dialect);
+            notes.extend(shell_notes);
+            shell
+        };
+        let indent_of = |line: &str| line.len() - line.trim_start().len();
+
+        let mut block: Option<CiBlock> = None;
+        let mut rewritten = String::with_capacity(content.len());
+        for line in content.split_inclusive('\n') {
+            let body = line.trim_end_matches(['\r', '\n']);
+            let ending = &line[body.len()..];
+            let indent = indent_of(body);
+
+            if let Some(current) = block {
+                let inside = body.trim().is_empty()
+                    || indent > current.key_column
+                    || (current.kind == CiBlockKind::List && indent == current.key_column && body.trim_start().starts_with("- "));
+                if !inside {
+                    block = None;
+                } else if current.kind == CiBlockKind::Scalar && !body.trim().is_empty() {
+                    rewritten.push_str(&format!("{}{}{}", &body[..indent], rewrite(body.trim_start(), notes), ending));
+                    continue;
+                } else if let Some(captures) = list_item.captures(body).filter(|_| command_key.captures(body).is_none()) {
+                    rewritten.push_str(&format!("{}{}{}", &captures[1], rewrite(&captures[2], notes), ending));
+                    continue;
+                }
+            }
+
+            if let Some(captures) = cache_line.captures(body).filter(|_| is_github) {
+                if target.manager == "bun" {
+                    notes.push("actions/setup-node cannot cache bun; remove its `cache:` or use oven-sh/setup-bun".to_string());
+                } else {
+                    rewritten.push_str(&format!("{}{}{}{}", &captures[1], target.manager, &captures[3], ending));
+                    continue;
+                }
+            }
+
+            let Some(captures) = command_key.captures(body) else {
+                rewritten.push_str(line);
+                continue;
+            };
+            let key_column = captures[1].len();
+            let value = &captures[4];
+            if value.is_empty() || value.starts_with('#') {
+                block = Some(CiBlock { key_column, kind: CiBlockKind::List });
+                rewritten.push_str(line);
+            } else if value.starts_with(['|', '>']) {
+                block = Some(CiBlock { key_column, kind: CiBlockKind::Scalar });
+                rewritten.push_str(line);
+            } else {
+                rewritten.push_str(&format!("{}{}{}{}{}", &captures[1], &captures[2], &captures[3], rewrite(value, notes), ending));
+            }
+        }
+
+        let setup = match target.dialect {
+            Dialect::Pnpm => Some(("pnpm/action-setup", "add a pnpm/action-setup step before actions/setup-node")),
+            Dialect::Bun => Some(("oven-sh/setup-bun", "add an oven-sh/setup-bun step")),
+            Dialect::YarnBerry => Some(("corepack enable", "run `corepack enable` before installing so the pinned Yarn is used")),
+            _ => None,
+        };
+        match setup {
+            Some((marker, note)) if is_github && content.contains("actions/setup-node") && !content.contains(marker) => {
+                notes.push(note.to_string());
+            }
+            Some(_) if !is_github && rewritten != content => {
+                notes.push(format!("make sure the CI image provides {}", target.manager));
+            }
+            _ => {}
+        }
+        if is_github && target.dialect != Dialect::Pnpm && content.contains("pnpm/action-setup") {
+            notes.push("the pnpm/action-setup step is no longer needed".to_string());
+        }
+
+        rewritten
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CiBlockKind {
+    /// The lines of a `|` or `>` block scalar.
+    Scalar,
+    /// `- command` items.
+    List,
+}
+
+/// The multi-line value of a command key, up to the next line indented no
+/// deeper than the key.
+#[derive(Clone, Copy)]
+struct CiBlock {
+    key_column: usize,
+    kind: CiBlockKind,
+}
+
+fn rewrite_scripts(
+    package: &mut serde_json::Map<String, Value>,
+    file_name: &str,
+    target: &MigrationTarget,
+    programs: &[&str],
+    notes: &mut Vec<String>,
+) {
+    let Some(scripts) = package.get_mut("scripts").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for (name, value) in scripts.iter_mut() {
+        let Some(script) = value.as_str() else {
+            continue;
+        };
+        let (rewritten, script_notes) = rewrite_shell(script, programs, target.dialect);
+        notes.extend(script_notes.into_iter().map(|note| format!("{} scripts.{}: {}", file_name, name, note)));
+        *value = Value::String(rewritten);
+    }
+}
+
+/// `<path>.bak`, or `<path>.bak.1`, `<path>.bak.2` and so on when that name
+/// is taken on disk or by an earlier backup of the plan.
+fn get_backup_path(path: &Path, changes: &[FileChange]) -> PathBuf {
+    let is_taken = |candidate: &Path| {
+        candidate.exists()
+            || changes
+                .iter()
+                .any(|change| matches!(change, FileChange::Backup { backup, .. } if backup == candidate))
+    };
+    let mut number = 0;
+    loop {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".bak");
+        if number > 0 {
+            name.push(format!(".{}", number));
+        }
+        let candidate = PathBuf::from(name);
+        if !is_taken(&candidate) {
+            return candidate;
+        }
+        number += 1;
+    }
+}
+
+/// Patterns from a package.json `workspaces` array or Yarn Classic's
+/// `{ "packages": [...] }` form.
+fn get_workspace_patterns(workspaces: &Value, notes: &mut Vec<String>) -> Vec<String> {
+    let patterns = match workspaces {
+        Value::Object(object) => {
+            let other_keys: Vec<&str> = object.keys().map(String::as_str).filter(|k| *k != "packages").collect();
+            if !other_keys.is_empty() {
+                notes.push(format!("`workspaces.{}` has no pnpm counterpart and was dropped", other_keys.join("`, `workspaces.")));
+            }
+            object.get("packages")
+        }
+        other => Some(other),
+    };
+    patterns
+        .and_then(Value::as_array)
+        .map(|patterns| patterns.iter().filter_map(|p| p.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+fn format_pnpm_workspace(patterns: &[String]) -> String {
+    let mut content = String::from("packages:\n");
+    for pattern in patterns {
+        content.push_str(&format!("  - '{}'\n", pattern.replace('\'', "''")));
+    }
+    content
+}
+
+/// The indentation of the first indented line, so rewritten JSON keeps the
+/// file's style.
+fn detect_indent(content: &str) -> String {
+    content
+        .lines()
+        .skip(1)
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+        .to_string()
+}
+
+fn to_json(value: &Value, indent: &str) -> Result<String> {
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    value.serialize(&mut serializer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+/// `value` as JSON with the indentation and final newline of `original`.
+fn format_json_like(value: &Value, original: &str) -> Result<String> {
+    let mut json = to_json(value, &detect_indent(original))?;
+    if original.ends_with('\n') {
+        json.push('\n');
+    }
+    Ok(json)
+}
+
+/// Writes to a sibling file first, so `path` never holds half the content.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let mut staged = path.as_os_str().to_owned();
+    staged.push(".fazrepo-tmp");
+    let staged = PathBuf::from(staged);
+    fs::write(&staged, content).with_context(|| format!("Failed to write {}", staged.display()))?;
+    fs::rename(&staged, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn apply_change(change: &FileChange) -> Result<()> {
+    match change {
+        FileChange::Backup { path, backup } => {
+            // A backup made since the plan was computed must not be overwritten.
+            if backup.exists() {
+                anyhow::bail!("{} already exists", backup.display());
+            }
+            fs::rename(path, backup)
+                .with_context(|| format!("Failed to move {} to {}", path.display(), backup.display()))
+        }
+        FileChange::Write { path, after, .. } => write_atomically(path, after),
+    }
+}
+
+fn undo_change(change: &FileChange) -> Result<()> {
+    match change {
+        FileChange::Backup { path, backup } => fs::rename(backup, path)
+            .with_context(|| format!("Failed to move {} back to {}", backup.display(), path.display())),
+        FileChange::Write { path, before: Some(before), .. } => write_atomically(path, before),
+        FileChange::Write { path, before: None, .. } => {
+            fs::remove_file(path).with_context(|| format!("Failed to delete {}", path.display()))
+        }
+    }
+}
+
+impl MigrationService for DefaultMigrationService {
+    fn plan(&self, detection: &ProjectDetection, target: &MigrationTarget) -> Result<MigrationPlan> {
+        let directory = &detection.directory;
+        let from = detection.manager.as_deref();
+        let programs = self.get_rewritten_programs(from, &target.manager);
+        let mut changes = Vec::new();
+        let mut notes = Vec::new();
+
+        let package_path = directory.join("package.json");
+        let before = fs::read_to_string(&package_path)
+            .with_context(|| format!("Failed to read {}", package_path.display()))?;
+        let original: Value = serde_json::from_str(&before)
+            .with_context(|| format!("Failed to parse {}", package_path.display()))?;
+        let mut package = original.clone();
+        let Some(fields) = package.as_object_mut() else {
+            anyhow::bail!("{} is not a JSON object", package_path.display());
+        };
+        self.plan_package_json(fields, detection, target, &programs, &mut changes, &mut notes)?;
+
+        // Only rewrite package.json when something changed, since
+        // serializing may reformat it.
+        if package != original {
+            changes.insert(
+                0,
+                FileChange::Write {
+                    after: format_json_like(&package, &before)?,
+                    path: package_path,
+                    before: Some(before),
+                },
+            );
+        }
+        self.plan_workspace_packages(directory, target, &programs, &mut changes, &mut notes)?;
+
+        let yarnrc = directory.join(".yarnrc.yml");
+        if target.dialect == Dialect::YarnBerry && !yarnrc.exists() {
+            changes.push(FileChange::Write {
+                path: yarnrc,
+                before: None,
+                after: "nodeLinker: node-modules\n".to_string(),
+            });
+            notes.push("Yarn is set to `nodeLinker: node-modules` so existing tooling keeps working; remove it from .yarnrc.yml to use Plug'n'Play".to_string());
+        }
+
+        for lockfile in detection.lockfiles.iter().filter(|l| l.manager != target.manager) {
+            let backup = get_backup_path(&lockfile.path, &changes);
+            changes.push(FileChange::Backup {
+                path: lockfile.path.clone(),
+                backup,
+            });
+        }
+
+        let workflows = directory.join(".github").join("workflows");
+        for path in self.find_ci_files(directory) {
+            let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let mut ci_notes = Vec::new();
+            let after = self.rewrite_ci_file(&content, path.starts_with(&workflows), target, &programs, &mut ci_notes);
+            let name = path.strip_prefix(directory).unwrap_or(&path).display().to_string();
+            notes.extend(ci_notes.into_iter().map(|note| format!("{}: {}", name, note)));
+            if after != content {
+                changes.push(FileChange::Write {
+                    path,
+                    before: Some(content),
+                    after,
+                });
+            }
+        }
+
+        Ok(MigrationPlan {
+            directory: directory.clone(),
+            from: from.map(str::to_string),
+            target: target.clone(),
+            changes,
+            notes,
+            install: ManagerAction::Install.to_native(&target.manager, &[])?,
+        })
+    }
+
+    fn apply(&self, plan: &MigrationPlan) -> Result<()> {
+        for (index, change) in plan.changes.iter().enumerate() {
+            let Err(e) = apply_change(change) else {
+                continue;
+            };
+            let failed: Vec<String> = plan.changes[..index]
+                .iter()
+                .rev()
+                .filter_map(|change| undo_change(change).err())
+                .map(|e| format!("{:#}", e))
+                .collect();
+            return Err(if failed.is_empty() {
+                e.context("Migration stopped and every change was undone")
+            } else {
+                e.context(format!("Migration stopped and could not undo everything: {}", failed.join("; ")))
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Lockfile;
+    use semver::Version;
+
+    #[test]
+    fn test_plan_npm_workspace_to_pnpm() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            "{\n    \"name\": \"repo\",\n    \"workspaces\": [\"packages/*\"],\n    \"scripts\": {\n        \"ci\": \"npm ci && npm run build -- --prod\"\n    }\n}\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join(".github/workflows")).unwrap();
+        fs::write(
+            dir.path().join(".github/workflows/ci.yml"),
+            "steps:\n  - uses: actions/setup-node@v4\n    with:\n      cache: npm\n  - run: npm ci\n",
+        )
+        .unwrap();
+
+        let mut detection = ProjectDetection::new(dir.path().to_path_buf());
+        detection.manager = Some("npm".to_string());
+        detection.lockfiles.push(Lockfile {
+            manager: "npm".to_string(),
+            path: dir.path().join("package-lock.json"),
+        });
+        let target = MigrationTarget {
+            manager: "pnpm".to_string(),
+            version: Version::new(9, 1, 0),
+            dialect: Dialect::Pnpm,
+        };
+
+        let plan = DefaultMigrationService::new().plan(&detection, &target).unwrap();
+        let FileChange::Write { after, .. } = &plan.changes[0] else {
+            panic!("package.json is not rewritten");
+        };
+        assert_eq!(
+            after,
+            "{\n    \"name\": \"repo\",\n    \"scripts\": {\n        \"ci\": \"pnpm install --frozen-lockfile && pnpm run build --prod\"\n    },\n    \"packageManager\": \"pnpm@9.1.0\"\n}\n"
+        );
+        let FileChange::Write { after, .. } = &plan.changes[1] else {
+            panic!("pnpm-workspace.yaml is not created");
+        };
+        assert_eq!(after, "packages:\n  - 'packages/*'\n");
+        assert!(matches!(&plan.changes[2], FileChange::Backup { backup, .. } if backup.ends_with("package-lock.json.bak")));
+        let FileChange::Write { after, .. } = &plan.changes[3] else {
+            panic!("the workflow is not rewritten");
+        };
+        assert!(after.contains("      cache: pnpm\n  - run: pnpm install --frozen-lockfile\n"));
+        assert_eq!(plan.notes, vec![".github/workflows/ci.yml: add a pnpm/action-setup step before actions/setup-node"]);
+    }
+
+    fn target(manager: &str, dialect: Dialect) -> MigrationTarget {
+        MigrationTarget {
+            manager: manager.to_string(),
+            version: Version::new(10, 8, 2),
+            dialect,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_ci_file_only_touches_commands() {
+        let workflow = "\
+steps:
+  - name: npm install
+    env:
+      INSTALLER: npm ci
+  - run: |
+      npm ci
+      npm test
+  - run: npm run lint
+";
+        let mut notes = Vec::new();
+        let service = DefaultMigrationService::new();
+        let after = service.rewrite_ci_file(workflow, true, &target("pnpm", Dialect::Pnpm), &["npm"], &mut notes);
+        assert_eq!(
+            after,
+            "\
+steps:
+  - name: npm install
+    env:
+      INSTALLER: npm ci
+  - run: |
+      pnpm install --frozen-lockfile
+      pnpm run test
+  - run: pnpm run lint
+"
+        );
+
+        let gitlab = "test:\n  script:\n  - npm ci\n  - npm test\n  variables:\n    CMD: npm ci\n";
+        let after = service.rewrite_ci_file(gitlab, false, &target("pnpm", Dialect::Pnpm), &["npm"], &mut notes);
+        assert_eq!(
+            after,
+            "test:\n  script:\n  - pnpm install --frozen-lockfile\n  - pnpm run test\n  variables:\n    CMD: npm ci\n"
+        );
+    }
+
+    #[test]
+    fn test_plan_pnpm_workspace_to_npm() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("package.json"), "{\n  \"name\": \"repo\"\n}\n").unwrap();
+        fs::write(root.join(PNPM_WORKSPACE_FILE), "packages:\n  - 'packages/*'\nonlyBuiltDependencies:\n  - esbuild\n").unwrap();
+        fs::write(root.join("pnpm-lock.yaml"), "").unwrap();
+        fs::write(root.join("pnpm-lock.yaml.bak"), "").unwrap();
+        fs::create_dir_all(root.join("packages/ui")).unwrap();
+        fs::write(root.join("packages/ui/package.json"), "{\n  \"name\": \"ui\",\n  \"scripts\": {\n    \"build\": \"pnpm exec tsc\"\n  }\n}\n").unwrap();
+
+        let mut detection = ProjectDetection::new(root.to_path_buf());
+        detection.manager = Some("pnpm".to_string());
+        detection.lockfiles.push(Lockfile {
+            manager: "pnpm".to_string(),
+            path: root.join("pnpm-lock.yaml"),
+        });
+
+        let plan = DefaultMigrationService::new().plan(&detection, &target("npm", Dialect::Npm)).unwrap();
+        let paths: Vec<String> = plan.changes.iter().map(|c| c.get_path().strip_prefix(root).unwrap().display().to_string()).collect();
+        assert_eq!(paths, vec!["package.json", PNPM_WORKSPACE_FILE, "packages/ui/package.json", "pnpm-lock.yaml"]);
+        assert!(matches!(&plan.changes[1], FileChange::Backup { backup, .. } if backup.ends_with("pnpm-workspace.yaml.bak")));
+        let FileChange::Write { after, .. } = &plan.changes[2] else {
+            panic!("the workspace package is not rewritten");
+        };
+        assert_eq!(after, "{\n  \"name\": \"ui\",\n  \"scripts\": {\n    \"build\": \"npx tsc\"\n  }\n}\n");
+        assert!(matches!(&plan.changes[3], FileChange::Backup { backup, .. } if backup.ends_with("pnpm-lock.yaml.bak.1")));
+        assert!(plan.notes[0].contains("onlyBuiltDependencies"));
+    }
+
+    #[test]
+    fn test_apply_undoes_changes_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let lockfile = dir.path().join("yarn.lock");
+        let manifest = dir.path().join("package.json");
+        fs::write(&lockfile, "lock").unwrap();
+        fs::write(&manifest, "{}").unwrap();
+
+        let plan = MigrationPlan {
+            directory: dir.path().to_path_buf(),
+            from: Some("yarn".to_string()),
+            target: target("npm", Dialect::Npm),
+            changes: vec![
+                FileChange::Backup {
+                    path: lockfile.clone(),
+                    backup: dir.path().join("yarn.lock.bak"),
+                },
+                FileChange::Write {
+                    path: manifest.clone(),
+                    before: Some("{}".to_string()),
+                    after: "{\"packageManager\": \"npm@10.8.2\"}".to_string(),
+                },
+                FileChange::Write {
+                    path: dir.path().join("missing").join("file"),
+                    before: None,
+                    after: String::new(),
+                },
+            ],
+            notes: Vec::new(),
+            install: ManagerAction::Install.to_native("npm", &[]).unwrap(),
+        };
+
+        let error = DefaultMigrationService::new().apply(&plan).unwrap_err();
+        assert!(format!("{:#}", error).starts_with("Migration stopped and every change was undone"));
+        assert_eq!(fs::read_to_string(&lockfile).unwrap(), "lock");
+        assert_eq!(fs::read_to_string(&manifest).unwrap(), "{}");
+        assert!(!dir.path().join("yarn.lock.bak").exists());
+    }
+}
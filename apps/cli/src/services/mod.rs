@@ -2,8 +2,32 @@ pub mod package_manager_service;
 pub mod project_service;
 pub mod config_service;
 pub mod output_service;
+pub mod manifest_service;
+pub mod detection_service;
+pub mod yarn_service;
+pub mod cache_service;
+pub mod registry_service;
+pub mod globals_service;
+pub mod cache_usage_service;
+pub mod snapshot_service;
+pub mod process_service;
+pub mod migration_service;
+pub mod workspace_service;
+pub mod node_version_service;
 
 pub use package_manager_service::*;
 pub use project_service::*;
 pub use config_service::*;
-pub use output_service::*; 
\ No newline at end of file
+pub use output_service::*;
+pub use manifest_service::*;
+pub use detection_service::*;
+pub use yarn_service::*;
+pub use cache_service::*;
+pub use registry_service::*;
+pub use globals_service::*;
+pub use cache_usage_service::*;
+pub use snapshot_service::*;
+pub use process_service::*;
+pub use migration_service::*;
+pub use workspace_service::*;
+pub use node_version_service::*;
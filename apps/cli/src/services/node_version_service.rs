@@ -0,0 +1,124 @@
+use crate::models::{NodePin, NodeVersionSource};
+use crate::services::{DefaultManifestService, ManifestService};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub trait NodeVersionService {
+    /// The nearest pin of each kind at or above `directory`, in
+    /// `NodeVersionSource::ALL` order.
+    fn find_pins(&self, directory: &Path) -> Result<Vec<NodePin>>;
+}
+
+#[derive(Clone)]
+pub struct DefaultNodeVersionService {
+    manifest_service: DefaultManifestService,
+    home_dir: Option<PathBuf>,
+}
+
+impl DefaultNodeVersionService {
+    pub fn new() -> Self {
+        Self {
+            manifest_service: DefaultManifestService::new(),
+            home_dir: dirs::home_dir(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_home_dir(mut self, home_dir: Option<PathBuf>) -> Self {
+        self.home_dir = home_dir;
+        self
+    }
+
+    /// The version in `directory`, if it has a pin of this kind. Files
+    /// without a Node entry are skipped so the search goes on upwards.
+    fn read_pin(&self, source: NodeVersionSource, directory: &Path) -> Result<Option<NodePin>> {
+        let path = match source {
+            NodeVersionSource::Nvmrc => directory.join(".nvmrc"),
+            NodeVersionSource::NodeVersion => directory.join(".node-version"),
+            NodeVersionSource::Volta => directory.join("package.json"),
+            NodeVersionSource::ToolVersions => directory.join(".tool-versions"),
+        };
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let raw = if source == NodeVersionSource::Volta {
+            self.manifest_service
+                .read_package_json(&path)?
+                .volta
+                .and_then(|volta| volta.node)
+        } else {
+            let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let mut lines = content
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim())
+                .filter(|line| !line.is_empty());
+            if source == NodeVersionSource::ToolVersions {
+                // `nodejs 20.11.0`, or `node` for mise; later versions are fallbacks.
+                lines
+                    .map(|line| line.split_whitespace().collect::<Vec<_>>())
+                    .find(|fields| fields.len() > 1 && (fields[0] == "nodejs" || fields[0] == "node"))
+                    .map(|fields| fields[1].to_string())
+            } else {
+                lines.next().map(str::to_string)
+            }
+        };
+
+        Ok(raw.map(|raw| NodePin { source, path, raw }))
+    }
+}
+
+impl NodeVersionService for DefaultNodeVersionService {
+    fn find_pins(&self, directory: &Path) -> Result<Vec<NodePin>> {
+        // Files in the home directory are user-wide defaults, not project pins.
+        let directories: Vec<&Path> = directory
+            .ancestors()
+            .take_while(|dir| self.home_dir.as_deref() != Some(*dir))
+            .collect();
+
+        let mut pins = Vec::new();
+        for source in NodeVersionSource::ALL {
+            for dir in &directories {
+                if let Some(pin) = self.read_pin(source, dir)? {
+                    pins.push(pin);
+                    break;
+                }
+            }
+        }
+        Ok(pins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_pins() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        let nested = root.join("packages").join("app");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.path().join(".nvmrc"), "16\n").unwrap();
+        fs::write(root.join(".nvmrc"), "# comment\nv20.11.0\n").unwrap();
+        fs::write(root.join(".tool-versions"), "python 3.12.1\nnodejs 20.11.0 18.19.0\n").unwrap();
+        fs::write(nested.join(".tool-versions"), "python 3.11.0\n").unwrap();
+        fs::write(nested.join("package.json"), r#"{"name": "app", "volta": {"node": "18.19.0"}}"#).unwrap();
+
+        let pins = DefaultNodeVersionService::new()
+            .with_home_dir(Some(dir.path().to_path_buf()))
+            .find_pins(&nested)
+            .unwrap();
+        let found: Vec<(NodeVersionSource, &str)> = pins.iter().map(|p| (p.source, p.raw.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (NodeVersionSource::Nvmrc, "v20.11.0"),
+                (NodeVersionSource::Volta, "18.19.0"),
+                (NodeVersionSource::ToolVersions, "20.11.0"),
+            ]
+        );
+        assert_eq!(pins[2].path, root.join(".tool-versions"));
+    }
+}
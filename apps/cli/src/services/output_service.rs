@@ -1,13 +1,36 @@
-use crate::models::{PackageManagerCheckResult, ProjectGenerationResult, ProjectTemplate};
+use crate::models::{
+    format_size, redact_url, AuthStatus, CacheUsageReport, CheckStatus, DiagnosticCategory, DoctorReport, DetectionSource, Ecosystem, GlobalsReport, ManagerKind, ManagerState, NativeCommand, NodeVersionCheck, PackageManagerCheckResult, PolicyReport, ProjectDetection, ProjectGenerationResult, ProjectTemplate, RegistryReport, RequirementStatus, Severity, SnapshotDiff, SourcedValue, Translation, Workspace, diff_lines, DiffLine, FileChange, MigrationPlan,
+};
 use colored::*;
+use crate::utils::constants::VERSION;
 
 pub trait OutputService {
     fn display_package_manager_results(&self, results: &[PackageManagerCheckResult], detailed: bool);
-    fn display_project_templates(&self, templates: &[ProjectTemplate]);
+    fn display_corepack_status(&self, corepack: &PackageManagerCheckResult, shims: &[String]);
+    fn display_detection(&self, detection: &ProjectDetection, installed: Option<&PackageManagerCheckResult>);
+    fn display_registry_report(&self, report: &RegistryReport);
+    fn display_globals(&self, report: &GlobalsReport);
+    fn display_cache_usage(&self, report: &CacheUsageReport);
+    fn display_doctor_report(&self, report: &DoctorReport);
+    fn display_json(&self, json: &str);
+    fn display_snapshot_diff(&self, left: &str, right: &str, diff: &SnapshotDiff);
+    fn display_policy_report(&self, report: &PolicyReport);
+    /// Prints the translated command; to stderr when it is about to run, so
+    /// the command's own output stays clean.
+    fn display_native_command(&self, command: &NativeCommand, print_only: bool);
+    /// A single translation prints only the command on stdout, so it can be
+    /// pasted or captured; its notes go to stderr.
+    fn display_translations(&self, translations: &[Translation]);
+    /// Lists the planned file changes, with their diffs when `show_diff`.
+    fn display_migration_plan(&self, plan: &MigrationPlan, show_diff: bool);
+    fn display_workspace(&self, workspace: &Workspace);
+    fn display_project_templates(&self, templates: &[ProjectTemplate], warnings: &[String]);
     fn display_project_generation_result(&self, result: &ProjectGenerationResult);
     fn display_version(&self);
     fn display_init_success(&self);
     fn display_error(&self, message: &str);
+    /// Printed to stderr so it never mixes with JSON or a script's output.
+    fn display_warning(&self, message: &str);
     fn display_success(&self, message: &str);
     fn display_info(&self, message: &str);
 }
@@ -19,6 +42,282 @@ impl ColoredOutputService {
     pub fn new() -> Self {
         Self
     }
+
+    fn display_check_result(&self, result: &PackageManagerCheckResult, detailed: bool) {
+        let pm = &result.package_manager;
+        
+        if result.success {
+            let cached = if result.from_cache { " ⚡ cached".dimmed() } else { "".normal() };
+            if detailed {
+                println!(
+                    "{} {} {}{}",
+                    "✅".bright_green(),
+                    pm.name.bright_cyan().bold(),
+                    pm.get_version_display().bright_white(),
+                    cached
+                );
+                println!("   📍 Path: {}", pm.get_path_display().dimmed());
+            } else {
+                println!(
+                    "{} {} {} ({}){}",
+                    "✅".bright_green(),
+                    pm.name.bright_cyan().bold(),
+                    pm.get_version_display().bright_white(),
+                    pm.get_path_display().dimmed(),
+                    cached
+                );
+            }
+        } else {
+            let status = match result.status {
+                CheckStatus::NotInstalled => "not installed",
+                CheckStatus::TimedOut => "timed out",
+                _ => "version check failed",
+            };
+            if detailed {
+                println!(
+                    "{} {} {}",
+                    "⚠️".bright_yellow(),
+                    pm.name.bright_cyan().bold(),
+                    status.bright_red()
+                );
+                if let Some(path) = &pm.path {
+                    println!("   📍 Path: {}", path.display().to_string().dimmed());
+                }
+                if let Some(error) = &result.error_message {
+                    println!("   ❌ Error: {}", error.dimmed());
+                }
+            } else if result.status == CheckStatus::TimedOut {
+                println!(
+                    "{} {} {}",
+                    "⏱️".bright_yellow(),
+                    pm.name.bright_cyan().bold(),
+                    status.bright_yellow()
+                );
+            } else {
+                println!(
+                    "{} {} {}",
+                    "❌".bright_red(),
+                    pm.name.bright_cyan().bold(),
+                    status.bright_red()
+                );
+            }
+        }
+
+        if let Some(requirement) = &result.requirement {
+            if requirement.is_satisfied() {
+                println!(
+                    "   {} {} ({})",
+                    "✔ requires".bright_green(),
+                    requirement.expression.bright_white(),
+                    requirement.message.dimmed()
+                );
+            } else {
+                println!(
+                    "   {} {} ({})",
+                    "✘ requires".bright_red(),
+                    requirement.expression.bright_white(),
+                    requirement.message.bright_red()
+                );
+            }
+        }
+
+        if let Some(engine) = &result.engine {
+            let status = match engine.status {
+                RequirementStatus::Satisfied => "✔ engines".bright_green(),
+                RequirementStatus::Missing => "- engines".dimmed(),
+                _ => "✘ engines".bright_red(),
+            };
+            println!(
+                "   {} {} ({})",
+                status,
+                engine.expression.bright_white(),
+                engine.message.dimmed()
+            );
+        }
+
+        if let Some(yarn) = &result.yarn {
+            let flavor = yarn.flavor.map(|f| f.get_display_name()).unwrap_or("Yarn");
+            let linker = yarn.linker.as_deref().unwrap_or("unknown");
+            println!(
+                "   🧶 {} {}",
+                flavor.bright_white(),
+                format!("({} linker)", linker).dimmed()
+            );
+            if let Some(yarn_path) = &yarn.yarn_path {
+                println!(
+                    "   📦 Repo-local yarnPath: {} {}",
+                    yarn_path.display().to_string().bright_white(),
+                    format!(
+                        "(global: {})",
+                        yarn.global_version.as_deref().unwrap_or("not installed")
+                    )
+                    .dimmed()
+                );
+            }
+            if detailed {
+                if let Some(yarnrc) = &yarn.yarnrc {
+                    println!("   📄 Config: {}", yarnrc.display().to_string().dimmed());
+                }
+                for release in &yarn.releases {
+                    println!("   🗃️ Release: {}", release.display().to_string().dimmed());
+                }
+            }
+        }
+
+        if !result.installations.is_empty() {
+            self.display_installations(result);
+        }
+
+        if let Some(pin) = &result.pin {
+            let status = if pin.matches {
+                "✔ match".bright_green()
+            } else {
+                "✘ mismatch".bright_red()
+            };
+            println!(
+                "   📌 pinned {} / installed {} / {}",
+                pin.pinned.bright_white(),
+                pin.installed.as_deref().unwrap_or("none").bright_white(),
+                status
+            );
+            if detailed {
+                println!("   📄 Pinned in: {}", pin.source.display().to_string().dimmed());
+            }
+        }
+
+        if let Some(node_version) = &result.node_version {
+            self.display_node_version(node_version, detailed);
+        }
+
+        if let Some(outdated) = &result.outdated {
+            match (&outdated.latest, &outdated.upgrade_command) {
+                (Some(latest), Some(command)) => println!(
+                    "   {} {} (run {})",
+                    "⬆️ update available:".bright_yellow(),
+                    latest.to_string().bright_white(),
+                    command.bright_cyan()
+                ),
+                (Some(latest), None) => println!(
+                    "   {} {}",
+                    "✔ up to date".bright_green(),
+                    format!("(latest {})", latest).dimmed()
+                ),
+                (None, _) => println!(
+                    "   {} {}",
+                    "- latest version unknown:".dimmed(),
+                    outdated.error.as_deref().unwrap_or("lookup failed").dimmed()
+                ),
+            }
+        }
+    }
+
+    fn display_node_version(&self, node_version: &NodeVersionCheck, detailed: bool) {
+        for check in &node_version.pins {
+            let status = match check.matches {
+                Some(true) => "✔ match".bright_green(),
+                Some(false) => "✘ mismatch".bright_red(),
+                None => "? alias, not compared".bright_yellow(),
+            };
+            println!(
+                "   📌 {} {} / installed {} / {}",
+                check.pin.source.get_display(),
+                check.pin.raw.bright_white(),
+                node_version.installed.as_deref().unwrap_or("none").bright_white(),
+                status
+            );
+            if let Some(switch) = &check.switch {
+                println!(
+                    "      ↪ {} switches it: run {}{}",
+                    switch.manager.bright_white(),
+                    switch.command.bright_cyan(),
+                    if switch.detected { "".normal() } else { format!(" ({} not found)", switch.manager).dimmed() }
+                );
+            }
+            if detailed {
+                println!("   📄 Pinned in: {}", check.pin.path.display().to_string().dimmed());
+            }
+        }
+
+        let conflicting = node_version.get_conflicting();
+        if !conflicting.is_empty() {
+            let pins: Vec<String> = conflicting
+                .iter()
+                .map(|pin| format!("{} {}", pin.source.get_display(), pin.raw))
+                .collect();
+            println!(
+                "   {} {}",
+                "⚠️ Conflicting Node pins:".bright_yellow(),
+                pins.join(", ").bright_yellow()
+            );
+        }
+    }
+
+    fn format_sourced(&self, value: &SourcedValue) -> String {
+        format!(
+            "{} {}",
+            redact_url(&value.value).bright_white(),
+            format!("({})", value.source.get_display()).dimmed()
+        )
+    }
+
+    /// Only says whether credentials exist and where; never their value.
+    fn format_auth(&self, auth: &AuthStatus) -> String {
+        match auth {
+            AuthStatus::Present(source) => format!(
+                "{} {}",
+                "token present".bright_green(),
+                format!("({})", source.get_display()).dimmed()
+            ),
+            AuthStatus::Empty(source) => format!(
+                "{} {}",
+                "configured but empty".bright_yellow(),
+                format!("({})", source.get_display()).dimmed()
+            ),
+            AuthStatus::Missing => "none".dimmed().to_string(),
+        }
+    }
+
+    fn display_installations(&self, result: &PackageManagerCheckResult) {
+        let conflicts = result.get_shadowed_conflicts();
+
+        println!("   🔎 Installations on PATH:");
+        for installation in &result.installations {
+            let marker = if installation.is_active {
+                "▶".bright_green()
+            } else {
+                " ".normal()
+            };
+            let target = installation
+                .target
+                .as_ref()
+                .map(|t| format!(" → {}", t.display()))
+                .unwrap_or_default();
+            let version = match installation.status {
+                CheckStatus::Ok => installation
+                    .version
+                    .clone()
+                    .unwrap_or_else(|| "unknown".to_string())
+                    .bright_white(),
+                CheckStatus::TimedOut => "timed out".bright_yellow(),
+                _ => "version check failed".bright_red(),
+            };
+
+            print!(
+                "     {} {}{} {}",
+                marker,
+                installation.path.display(),
+                target.dimmed(),
+                version
+            );
+            if installation.is_active {
+                println!(" {}", "(active)".bright_green());
+            } else if conflicts.iter().any(|c| c.path == installation.path) {
+                println!(" {}", "⚠️ shadowed, different version".bright_yellow());
+            } else {
+                println!(" {}", "(shadowed)".dimmed());
+            }
+        }
+    }
 }
 
 impl OutputService for ColoredOutputService {
@@ -31,54 +330,620 @@ impl OutputService for ColoredOutputService {
         );
         println!();
 
-        for result in results {
-            let pm = &result.package_manager;
-            
-            if result.success {
-                if detailed {
+        // Ecosystem headers only add noise when a single ecosystem is checked.
+        let ecosystems: Vec<_> = Ecosystem::ALL
+            .into_iter()
+            .filter(|ecosystem| results.iter().any(|r| r.package_manager.ecosystem == *ecosystem))
+            .collect();
+        for ecosystem in &ecosystems {
+            if ecosystems.len() > 1 {
+                println!("{}", format!("── {} ──", ecosystem.get_display_name()).bright_blue().bold());
+                println!();
+            }
+
+            for kind in ManagerKind::ALL {
+                let group: Vec<_> = results
+                    .iter()
+                    .filter(|r| r.package_manager.ecosystem == *ecosystem && r.package_manager.kind == kind)
+                    .collect();
+                if group.is_empty() {
+                    continue;
+                }
+
+                println!("{}", format!("{}:", kind.get_display_name()).bright_magenta().bold());
+                for result in group {
+                    self.display_check_result(result, detailed);
+                }
+                println!();
+            }
+        }
+
+        let outdated: Vec<_> = results.iter().filter_map(|r| r.outdated.as_ref()).collect();
+        if let Some(first) = outdated.first() {
+            let behind = outdated.iter().filter(|o| o.is_outdated).count();
+            let looked_up = outdated.iter().filter(|o| o.latest.is_some()).count();
+            let summary = format!("{} of {} behind", behind, looked_up);
+            println!(
+                "{} {} {}",
+                "⬆️ Updates:".bright_blue().bold(),
+                if behind == 0 {
+                    summary.bright_green()
+                } else {
+                    summary.bright_yellow()
+                },
+                if first.registry.is_empty() {
+                    String::new().dimmed()
+                } else {
+                    format!("(registry {})", redact_url(&first.registry)).dimmed()
+                }
+            );
+        }
+
+        let requirements: Vec<_> = results.iter().filter_map(|r| r.requirement.as_ref()).collect();
+        if !requirements.is_empty() {
+            let passed = requirements.iter().filter(|r| r.is_satisfied()).count();
+            let failed = requirements.len() - passed;
+            let failed_display = format!("{} failed", failed);
+            println!(
+                "{} {}, {}",
+                "📋 Requirements:".bright_blue().bold(),
+                format!("{} passed", passed).bright_green(),
+                if failed == 0 {
+                    failed_display.dimmed()
+                } else {
+                    failed_display.bright_red()
+                }
+            );
+        }
+    }
+
+    fn display_corepack_status(&self, corepack: &PackageManagerCheckResult, shims: &[String]) {
+        if !corepack.package_manager.is_installed {
+            return;
+        }
+
+        if shims.is_empty() {
+            println!(
+                "{} {} (run {} to let it manage yarn and pnpm)",
+                "🧰 Corepack:".bright_blue().bold(),
+                "installed but not enabled".bright_yellow(),
+                "corepack enable".bright_cyan()
+            );
+        } else {
+            println!(
+                "{} {} {}",
+                "🧰 Corepack:".bright_blue().bold(),
+                "enabled for".bright_green(),
+                shims.join(", ").bright_white()
+            );
+        }
+    }
+
+    fn display_detection(&self, detection: &ProjectDetection, installed: Option<&PackageManagerCheckResult>) {
+        println!("{}", "🔎 Detecting project package manager...".bright_blue().bold());
+        println!();
+        println!("📁 Project: {}", detection.directory.display().to_string().bright_cyan());
+
+        match &detection.manager {
+            Some(manager) => {
+                let source = match detection.source {
+                    Some(DetectionSource::PackageManagerField) => "packageManager field",
+                    _ => "lockfile",
+                };
+                println!(
+                    "📦 Package manager: {} {}",
+                    manager.bright_green().bold(),
+                    format!("(from {})", source).dimmed()
+                );
+            }
+            None => println!("📦 Package manager: {}", "unknown".bright_yellow()),
+        }
+
+        if let Some(pin) = &detection.pin {
+            println!("📌 packageManager: {}", pin.raw.bright_white());
+        }
+
+        if detection.lockfiles.is_empty() {
+            println!("🔒 Lockfiles: {}", "none".dimmed());
+        } else {
+            println!("🔒 Lockfiles:");
+            for lockfile in &detection.lockfiles {
+                println!(
+                    "   {} {}",
+                    lockfile.path.display().to_string().bright_white(),
+                    format!("({})", lockfile.manager).dimmed()
+                );
+            }
+        }
+
+        if let Some(result) = installed.filter(|r| r.package_manager.is_installed) {
+            println!(
+                "✅ Installed: {} {}",
+                result.package_manager.get_version_display().bright_white(),
+                format!("({})", result.package_manager.get_path_display()).dimmed()
+            );
+        }
+
+        if !detection.warnings.is_empty() {
+            println!();
+            for warning in &detection.warnings {
+                println!("{} {}", "⚠️".bright_yellow(), warning.bright_yellow());
+            }
+        }
+    }
+
+    fn display_registry_report(&self, report: &RegistryReport) {
+        println!("{}", "🌐 Registry configuration".bright_blue().bold());
+        println!();
+        println!("📁 Project: {}", report.directory.display().to_string().bright_cyan());
+
+        for config in &report.managers {
+            println!();
+            let marker = if report.is_registry_consistent(&config.manager) {
+                "".to_string()
+            } else {
+                format!(" {}", "⚠️ differs from the other managers".bright_yellow())
+            };
+            println!("{}{}", format!("📦 {}", config.label).bright_white().bold(), marker);
+            println!("   Registry: {}", self.format_sourced(&config.registry));
+            println!("   Auth: {}", self.format_auth(&config.auth));
+
+            if !config.scopes.is_empty() {
+                println!("   Scopes:");
+                for scoped in &config.scopes {
                     println!(
-                        "{} {} {}",
-                        "✅".bright_green(),
-                        pm.name.bright_cyan().bold(),
-                        pm.get_version_display().bright_white()
+                        "     {} → {}  auth: {}",
+                        scoped.scope.bright_cyan(),
+                        self.format_sourced(&scoped.registry),
+                        self.format_auth(&scoped.auth)
                     );
-                    println!("   📍 Path: {}", pm.get_path_display().dimmed());
-                } else {
+                }
+            }
+
+            for (label, proxy) in [("HTTP proxy", &config.http_proxy), ("HTTPS proxy", &config.https_proxy)] {
+                if let Some(proxy) = proxy {
+                    println!("   {}: {}", label, self.format_sourced(proxy));
+                }
+            }
+
+            if config.files.is_empty() {
+                println!("   Files: {}", "none".dimmed());
+            } else {
+                let files: Vec<String> = config.files.iter().map(|f| f.display().to_string()).collect();
+                println!("   Files: {}", files.join(", ").dimmed());
+            }
+        }
+
+        let groups = report.get_registry_groups();
+        let conflicts = report.get_scope_conflicts();
+        println!();
+        if groups.len() > 1 {
+            println!("{}", "⚠️ Managers resolve packages from different registries:".bright_yellow());
+            for (registry, managers) in &groups {
+                println!("   {} {}", registry.bright_white(), format!("({})", managers.join(", ")).dimmed());
+            }
+        }
+        for scope in &conflicts {
+            println!(
+                "{} {}",
+                "⚠️".bright_yellow(),
+                format!("{} points at different registries depending on the manager", scope).bright_yellow()
+            );
+        }
+        if groups.len() <= 1 && conflicts.is_empty() {
+            println!("{}", "✅ All managers use the same registries".bright_green());
+        }
+    }
+
+    fn display_globals(&self, report: &GlobalsReport) {
+        println!("{}", "🌍 Global packages".bright_blue().bold());
+
+        let conflicts = report.get_conflicts();
+        for listing in &report.listings {
+            println!();
+            let version = listing
+                .manager_version
+                .as_deref()
+                .map(|v| format!(" {}", v))
+                .unwrap_or_default();
+            match &listing.error {
+                Some(error) => {
                     println!(
-                        "{} {} {} ({})",
-                        "✅".bright_green(),
-                        pm.name.bright_cyan().bold(),
-                        pm.get_version_display().bright_white(),
-                        pm.get_path_display().dimmed()
+                        "{} {}",
+                        format!("📦 {}{}:", listing.manager, version).bright_white().bold(),
+                        error.bright_red()
                     );
+                    continue;
                 }
+                None => println!(
+                    "{} {}",
+                    format!("📦 {}{}", listing.manager, version).bright_white().bold(),
+                    format!("({} package{})", listing.packages.len(), if listing.packages.len() == 1 { "" } else { "s" }).dimmed()
+                ),
+            }
+
+            for package in &listing.packages {
+                let marker = if conflicts.iter().any(|c| c.name == package.name) {
+                    format!(" {}", "⚠️".bright_yellow())
+                } else {
+                    String::new()
+                };
+                println!("   {} {}{}", package.name.bright_cyan(), package.version.bright_white(), marker);
+            }
+        }
+
+        println!();
+        if conflicts.is_empty() {
+            println!("{}", "✅ No package is installed globally at different versions".bright_green());
+        } else {
+            println!(
+                "{}",
+                "⚠️ Installed globally through several managers at different versions:".bright_yellow()
+            );
+            for conflict in &conflicts {
+                let installs: Vec<String> = conflict
+                    .installs
+                    .iter()
+                    .map(|(manager, version)| format!("{} {}", manager, version))
+                    .collect();
+                println!("   {} → {}", conflict.name.bright_white().bold(), installs.join(", ").bright_yellow());
+            }
+        }
+    }
+
+    fn display_cache_usage(&self, report: &CacheUsageReport) {
+        println!("{}", "💾 Package manager caches".bright_blue().bold());
+        println!();
+
+        let width = report.usages.iter().map(|u| u.manager.len()).max().unwrap_or(0);
+        for usage in &report.usages {
+            let directory = usage
+                .directory
+                .as_ref()
+                .map(|d| d.display().to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            let size = match usage.size {
+                Some(size) => format_size(size).bright_white().bold().to_string(),
+                None => "-".dimmed().to_string(),
+            };
+            let freed = match usage.freed {
+                Some(freed) => format!(" {}", format!("(freed {})", format_size(freed)).bright_green()),
+                None => String::new(),
+            };
+            println!(
+                "📦 {:width$}  {:>10}  {}{}",
+                usage.manager.bright_cyan(),
+                size,
+                directory.dimmed(),
+                freed,
+                width = width
+            );
+            if let Some(error) = &usage.error {
+                println!("   {} {}", "❌".bright_red(), error.bright_red());
+            }
+        }
+
+        println!();
+        let total = format_size(report.get_total_size());
+        if report.pruned {
+            println!(
+                "{} {} {}",
+                "Total:".bright_blue().bold(),
+                total.bright_white().bold(),
+                format!("(freed {})", format_size(report.get_total_freed())).bright_green()
+            );
+        } else {
+            println!("{} {}", "Total:".bright_blue().bold(), total.bright_white().bold());
+        }
+    }
+
+    fn display_doctor_report(&self, report: &DoctorReport) {
+        println!("{}", "🩺 Diagnosing your environment...".bright_blue().bold());
+        println!();
+        println!("📁 Project: {}", report.directory.display().to_string().bright_cyan());
+
+        for category in DiagnosticCategory::ALL {
+            let findings: Vec<_> = report.findings.iter().filter(|f| f.category == category).collect();
+            if findings.is_empty() {
+                continue;
+            }
+
+            println!();
+            println!("{}", format!("── {} ──", category.get_display_name()).bright_blue().bold());
+            for finding in findings {
+                let line = match finding.severity {
+                    Severity::Ok => format!("✅ {}", finding.message.bright_white()),
+                    Severity::Info => format!("ℹ️  {}", finding.message.bright_blue()),
+                    Severity::Warning => format!("⚠️  {}", finding.message.bright_yellow()),
+                    Severity::Error => format!("❌ {}", finding.message.bright_red()),
+                };
+                println!("{}", line);
+                if let Some(fix) = &finding.fix {
+                    println!("   💡 {}", fix.dimmed());
+                }
+            }
+        }
+
+        let errors = report.count(Severity::Error);
+        let warnings = report.count(Severity::Warning);
+        println!();
+        if errors == 0 && warnings == 0 {
+            println!("{}", "✅ No problems found".bright_green().bold());
+        } else {
+            let summary = format!(
+                "{} error{}, {} warning{}",
+                errors,
+                if errors == 1 { "" } else { "s" },
+                warnings,
+                if warnings == 1 { "" } else { "s" }
+            );
+            if errors > 0 {
+                println!("{}", summary.bright_red().bold());
+            } else {
+                println!("{}", summary.bright_yellow().bold());
+            }
+        }
+    }
+
+    fn display_json(&self, json: &str) {
+        println!("{}", json);
+    }
+
+    fn display_policy_report(&self, report: &PolicyReport) {
+        println!("{}", "🛡️  Verifying toolchain policy...".bright_blue().bold());
+        println!();
+
+        for check in &report.checks {
+            if check.passed {
+                println!("{} {}: {}", "✅".bright_green(), check.rule.bright_cyan().bold(), check.message.dimmed());
             } else {
-                if detailed {
+                println!("{} {}: {}", "❌".bright_red(), check.rule.bright_cyan().bold(), check.message.bright_red());
+            }
+        }
+
+        println!();
+        let violations = report.get_violations().len();
+        if violations == 0 {
+            println!("{}", "✅ Policy satisfied".bright_green().bold());
+        } else {
+            println!(
+                "{}",
+                format!("❌ {} policy violation{}", violations, if violations == 1 { "" } else { "s" })
+                    .bright_red()
+                    .bold()
+            );
+        }
+    }
+
+    fn display_native_command(&self, command: &NativeCommand, print_only: bool) {
+        if print_only {
+            println!("{}", command.get_display());
+        } else {
+            eprintln!("{} {}", "▶".bright_blue(), command.get_display().dimmed());
+        }
+    }
+
+    fn display_translations(&self, translations: &[Translation]) {
+        if let [translation] = translations {
+            if let Some(command) = &translation.command {
+                println!("{}", command.get_display());
+            }
+            for note in &translation.notes {
+                eprintln!("{} {}", "⚠️".bright_yellow(), note.bright_yellow());
+            }
+            return;
+        }
+
+        let width = translations.iter().map(|t| t.dialect.get_name().len()).max().unwrap_or(0);
+        for translation in translations {
+            let name = format!("{:width$}", translation.dialect.get_name(), width = width);
+            match &translation.command {
+                Some(command) => println!("{}  {}", name.bright_cyan().bold(), command.get_display().bright_white()),
+                None => println!("{}  {}", name.bright_cyan().bold(), "—".dimmed()),
+            }
+            for note in &translation.notes {
+                println!("{:width$}  {} {}", "", "⚠️".bright_yellow(), note.bright_yellow(), width = width);
+            }
+        }
+    }
+
+    fn display_migration_plan(&self, plan: &MigrationPlan, show_diff: bool) {
+        println!(
+            "{} {} {} {} {} {}",
+            "🚚 Migrating".bright_blue().bold(),
+            plan.directory.display().to_string().bright_white(),
+            "from".bright_blue(),
+            plan.from.as_deref().unwrap_or("no detected manager").bright_red(),
+            "to".bright_blue(),
+            format!("{} {}", plan.target.manager, plan.target.version).bright_green()
+        );
+        println!();
+
+        if plan.is_empty() {
+            println!("{}", format!("✅ Nothing to change; the project already uses {}", plan.target.manager).bright_green().bold());
+            return;
+        }
+
+        let relative = |path: &std::path::Path| path.strip_prefix(&plan.directory).unwrap_or(path).display().to_string();
+        for change in &plan.changes {
+            let name = relative(change.get_path());
+            let (before, after) = match change {
+                FileChange::Write { before: None, after, .. } => {
+                    println!("✨ {} {}", "Create".bright_green().bold(), name.bright_white());
+                    (None, Some(after.as_str()))
+                }
+                FileChange::Write { before: Some(before), after, .. } => {
+                    println!("📝 {} {}", "Update".bright_yellow().bold(), name.bright_white());
+                    (Some(before.as_str()), Some(after.as_str()))
+                }
+                FileChange::Backup { backup, .. } => {
+                    println!("📦 {} {} → {}", "Move".bright_cyan().bold(), name.bright_white(), relative(backup).dimmed());
+                    continue;
+                }
+            };
+
+            if !show_diff {
+                continue;
+            }
+            let header = |text: Option<&str>, prefix: &str| match text {
+                Some(_) => format!("{}/{}", prefix, name),
+                None => "/dev/null".to_string(),
+            };
+            println!("{}", format!("--- {}", header(before, "a")).bold());
+            println!("{}", format!("+++ {}", header(after, "b")).bold());
+            for line in diff_lines(before.unwrap_or(""), after.unwrap_or("")) {
+                match line {
+                    DiffLine::Hunk(hunk) => println!("{}", hunk.bright_cyan()),
+                    DiffLine::Context(text) => println!(" {}", text.dimmed()),
+                    DiffLine::Removed(text) => println!("{}", format!("-{}", text).bright_red()),
+                    DiffLine::Added(text) => println!("{}", format!("+{}", text).bright_green()),
+                }
+            }
+            println!();
+        }
+
+        if !plan.notes.is_empty() {
+            println!();
+            println!("{}", "⚠️ Check by hand:".bright_yellow().bold());
+            for note in &plan.notes {
+                println!("   • {}", note.bright_yellow());
+            }
+        }
+
+        if show_diff {
+            println!();
+            println!("{} {}", "▶ Would run".bright_blue(), plan.install.get_display().bright_white());
+            println!("{}", "Dry run: nothing was changed".dimmed());
+        }
+    }
+
+    fn display_workspace(&self, workspace: &Workspace) {
+        let name = workspace.name.as_deref().unwrap_or("(unnamed)");
+        println!("{} {}", "📦 Workspace".bright_blue().bold(), name.bright_white().bold());
+        println!();
+        println!("📁 Root: {}", workspace.root.display().to_string().bright_cyan());
+        println!(
+            "📋 Packages from {}: {}",
+            workspace.source.get_display().bright_white(),
+            workspace.patterns.join(", ").dimmed()
+        );
+        if !workspace.task_runners.is_empty() {
+            println!("⚙️  Task runners: {}", workspace.task_runners.join(", ").bright_white());
+        }
+        println!();
+
+        if workspace.packages.is_empty() {
+            println!("{}", "No packages match the workspace patterns".bright_yellow());
+        }
+        let names: Vec<String> = workspace.packages.iter().map(|p| p.get_display_name()).collect();
+        let name_width = names.iter().map(String::len).max().unwrap_or(0);
+        let version_width = workspace
+            .packages
+            .iter()
+            .map(|p| p.version.as_deref().map_or(1, str::len))
+            .max()
+            .unwrap_or(0);
+        let path_width = workspace.packages.iter().map(|p| p.path.display().to_string().len()).max().unwrap_or(0);
+        for (package, name) in workspace.packages.iter().zip(&names) {
+            println!(
+                "   {}  {}  {}  {}",
+                format!("{:width$}", name, width = name_width).bright_cyan().bold(),
+                format!("{:width$}", package.version.as_deref().unwrap_or("—"), width = version_width).bright_white(),
+                format!("{:width$}", package.path.display().to_string(), width = path_width).dimmed(),
+                if package.private { "private".bright_magenta() } else { "".normal() }
+            );
+            for dependency in &package.internal_dependencies {
+                let kind = dependency.kind.get_label();
+                println!(
+                    "      {} {} {}{}",
+                    "└─".dimmed(),
+                    dependency.name.bright_white(),
+                    dependency.range.dimmed(),
+                    if kind.is_empty() { String::new() } else { format!(" ({})", kind) }.dimmed()
+                );
+            }
+        }
+
+        println!();
+        let count = workspace.packages.len();
+        println!("{}", format!("{} package{}", count, if count == 1 { "" } else { "s" }).bright_green().bold());
+
+        for warning in &workspace.warnings {
+            println!("{} {}", "⚠️".bright_yellow(), warning.bright_yellow());
+        }
+    }
+
+    fn display_snapshot_diff(&self, left: &str, right: &str, diff: &SnapshotDiff) {
+        println!("{}", "🔀 Comparing environments".bright_blue().bold());
+        println!();
+        println!("   {} {}", "−".bright_red(), left.bright_white());
+        println!("   {} {}", "+".bright_green(), right.bright_white());
+
+        if diff.is_empty() {
+            println!();
+            println!("{}", "✅ No differences".bright_green().bold());
+            return;
+        }
+
+        if !diff.platform.is_empty() {
+            println!();
+            println!("{}", "Platform:".bright_magenta().bold());
+            for change in &diff.platform {
+                println!("   {}: {} → {}", change.field, change.left.bright_red(), change.right.bright_green());
+            }
+        }
+
+        if !diff.managers.is_empty() {
+            println!();
+            println!("{}", "Package managers:".bright_magenta().bold());
+            let state = |state: &Option<ManagerState>| match state {
+                Some(state) => state.get_display(),
+                None => "not checked".to_string(),
+            };
+            for manager in &diff.managers {
+                if manager.is_path_only() {
+                    let path = |s: &Option<ManagerState>| {
+                        s.as_ref()
+                            .and_then(|s| s.path.as_ref())
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_default()
+                    };
                     println!(
-                        "{} {} {}",
-                        "⚠️".bright_yellow(),
-                        pm.name.bright_cyan().bold(),
-                        "version check failed".bright_red()
+                        "   {} {} {} → {}",
+                        manager.name.bright_white().bold(),
+                        "path".dimmed(),
+                        path(&manager.left).dimmed(),
+                        path(&manager.right).dimmed()
                     );
-                    if let Some(path) = &pm.path {
-                        println!("   📍 Path: {}", path.display().to_string().dimmed());
-                    }
-                    if let Some(error) = &result.error_message {
-                        println!("   ❌ Error: {}", error.dimmed());
-                    }
                 } else {
                     println!(
-                        "{} {} {}",
-                        "❌".bright_red(),
-                        pm.name.bright_cyan().bold(),
-                        "not installed".bright_red()
+                        "   {} {} → {}",
+                        manager.name.bright_white().bold(),
+                        state(&manager.left).bright_red(),
+                        state(&manager.right).bright_green()
                     );
                 }
             }
         }
+
+        if !diff.path_only_left.is_empty() || !diff.path_only_right.is_empty() {
+            println!();
+            println!("{}", "PATH entries:".bright_magenta().bold());
+            for entry in &diff.path_only_left {
+                println!("   {} {}", "−".bright_red(), entry.display().to_string().bright_red());
+            }
+            for entry in &diff.path_only_right {
+                println!("   {} {}", "+".bright_green(), entry.display().to_string().bright_green());
+            }
+        }
+
+        if !diff.unchanged.is_empty() {
+            println!();
+            println!("{} {}", "Identical:".dimmed(), diff.unchanged.join(", ").dimmed());
+        }
     }
 
-    fn display_project_templates(&self, templates: &[ProjectTemplate]) {
+    fn display_project_templates(&self, templates: &[ProjectTemplate], warnings: &[String]) {
         println!("{}", "📋 Available Project Templates:".bright_blue().bold());
         println!();
 
@@ -91,10 +956,26 @@ impl OutputService for ColoredOutputService {
             );
             
             println!("  Category: {}", format!("{:?}", template.category).bright_green());
-            println!("  Technologies: {}", template.technologies.join(", ").bright_yellow());
-            println!("  Features: {}", template.features.join(", ").dimmed());
+            if !template.technologies.is_empty() {
+                println!("  Technologies: {}", template.technologies.join(", ").bright_yellow());
+            }
+            if !template.features.is_empty() {
+                println!("  Features: {}", template.features.join(", ").dimmed());
+            }
+            match &template.overrides {
+                Some(overridden) => println!(
+                    "  Source: {} {}",
+                    template.source.get_display().bright_cyan(),
+                    format!("(overrides {})", overridden.get_display()).dimmed()
+                ),
+                None => println!("  Source: {}", template.source.get_display().bright_cyan()),
+            }
             println!();
         }
+
+        for warning in warnings {
+            println!("{} {}", "⚠️".bright_yellow(), warning.bright_yellow());
+        }
     }
 
     fn display_project_generation_result(&self, result: &ProjectGenerationResult) {
@@ -129,7 +1010,7 @@ impl OutputService for ColoredOutputService {
         println!(
             "{} {}",
             "fazrepo".bright_cyan().bold(),
-            env!("CARGO_PKG_VERSION").bright_white()
+            VERSION.bright_white()
         );
         println!("A CLI tool for full-stack project generation and package manager checking");
     }
@@ -147,6 +1028,10 @@ impl OutputService for ColoredOutputService {
         println!("{} {}", "❌ Error:".bright_red().bold(), message.bright_red());
     }
 
+    fn display_warning(&self, message: &str) {
+        eprintln!("{} {}", "⚠️ Warning:".bright_yellow().bold(), message.bright_yellow());
+    }
+
     fn display_success(&self, message: &str) {
         println!("{} {}", "✅ Success:".bright_green().bold(), message.bright_green());
     }
@@ -1,6 +1,15 @@
-use crate::models::{PackageManager, PackageManagerCheckResult};
+use crate::models::{
+    extract_version, CacheKey, Ecosystem, Installation, ManagerKind, PackageManager,
+    PackageManagerCheckResult,
+};
+use crate::services::{CacheService, DefaultCacheService};
+use crate::utils::constants::DEFAULT_PROBE_TIMEOUT_MS;
 use anyhow::Result;
-use std::process::Command;
+use futures::future::join_all;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
 use which;
 
 #[async_trait::async_trait]
@@ -8,14 +17,150 @@ pub trait PackageManagerService {
     fn get_supported_managers(&self) -> Vec<PackageManager>;
     async fn check_manager(&self, manager: &PackageManager) -> Result<PackageManagerCheckResult>;
     async fn check_managers(&self, managers: Vec<PackageManager>) -> Vec<PackageManagerCheckResult>;
+    async fn find_installations(&self, manager: &PackageManager) -> Vec<Installation>;
+    async fn check_binary(&self, manager: &PackageManager, path: &Path) -> PackageManagerCheckResult;
+    fn detect_corepack_shims(&self) -> Vec<String>;
 }
 
 #[derive(Clone)]
-pub struct DefaultPackageManagerService;
+pub struct DefaultPackageManagerService {
+    probe_timeout: Duration,
+    custom_tools: Vec<PackageManager>,
+    cache: Option<DefaultCacheService>,
+}
 
 impl DefaultPackageManagerService {
     pub fn new() -> Self {
-        Self
+        Self {
+            probe_timeout: Duration::from_millis(DEFAULT_PROBE_TIMEOUT_MS),
+            custom_tools: Vec::new(),
+            cache: None,
+        }
+    }
+
+    pub fn with_probe_timeout(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = timeout;
+        self
+    }
+
+    /// Adds tools declared in `.fazrepo`. A tool named like a built-in entry
+    /// replaces it.
+    pub fn with_custom_tools(mut self, tools: Vec<PackageManager>) -> Self {
+        self.custom_tools = tools;
+        self
+    }
+
+    /// Reuses successful results of earlier probes of the same binary.
+    pub fn with_cache(mut self, cache: DefaultCacheService) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    fn get_builtin_managers(&self) -> Vec<PackageManager> {
+        Ecosystem::ALL
+            .into_iter()
+            .flat_map(|ecosystem| {
+                self.get_ecosystem_pack(ecosystem)
+                    .into_iter()
+                    .map(move |pm| pm.with_ecosystem(ecosystem))
+            })
+            .collect()
+    }
+
+    fn get_ecosystem_pack(&self, ecosystem: Ecosystem) -> Vec<PackageManager> {
+        match ecosystem {
+            Ecosystem::JavaScript => self.get_javascript_pack(),
+            Ecosystem::Rust => vec![
+                PackageManager::new("cargo", "Cargo", "Rust package manager and build tool", "cargo")
+                    .with_version_pattern(r"^cargo (\d+\.\d+\.\d+\S*)"),
+                PackageManager::new("rustc", "Rust compiler", "Compiler for the Rust language", "rustc")
+                    .with_kind(ManagerKind::Runtime)
+                    .with_version_pattern(r"^rustc (\d+\.\d+\.\d+\S*)"),
+                PackageManager::new("rustup", "rustup", "Rust toolchain installer", "rustup")
+                    .with_kind(ManagerKind::VersionManager)
+                    .with_version_pattern(r"^rustup (\d+\.\d+\.\d+\S*)"),
+            ],
+            Ecosystem::Python => {
+                let (python, pip) = if cfg!(target_os = "windows") {
+                    ("python", "pip")
+                } else {
+                    ("python3", "pip3")
+                };
+                vec![
+                    PackageManager::new("pip", "pip", "Package installer for Python", pip)
+                        .with_version_pattern(r"^pip (\d+\.\d+(?:\.\d+)?)")
+                        .with_cache_commands(&["cache", "dir"], &["cache", "purge"]),
+                    PackageManager::new("uv", "uv", "Fast Python package and project manager", "uv")
+                        .with_version_pattern(r"^uv (\d+\.\d+\.\d+\S*)")
+                        .with_cache_commands(&["cache", "dir"], &["cache", "prune"]),
+                    PackageManager::new("poetry", "Poetry", "Python dependency management and packaging", "poetry")
+                        .with_version_pattern(r"^Poetry \(version (\d+\.\d+\.\d+\S*)\)"),
+                    PackageManager::new("python", "Python", "Python interpreter", python)
+                        .with_kind(ManagerKind::Runtime)
+                        .with_version_pattern(r"^Python (\d+\.\d+\.\d+\S*)"),
+                ]
+            }
+            Ecosystem::Go => vec![
+                PackageManager::new("go", "Go", "Go toolchain and module manager", "go")
+                    .with_version_args(vec!["version".to_string()])
+                    .with_version_pattern(r"^go version go(\d+\.\d+(?:\.\d+)?\S*)")
+                    .with_cache_commands(&["env", "GOMODCACHE"], &["clean", "-modcache"]),
+            ],
+            Ecosystem::Php => vec![
+                PackageManager::new("composer", "Composer", "Dependency manager for PHP", "composer")
+                    .with_version_pattern(r"^Composer (?:version )?(\d+\.\d+\.\d+\S*)")
+                    .with_cache_commands(&["config", "--global", "cache-dir"], &["clear-cache"]),
+                PackageManager::new("php", "PHP", "PHP interpreter", "php")
+                    .with_kind(ManagerKind::Runtime)
+                    .with_version_pattern(r"^PHP (\d+\.\d+\.\d+)"),
+            ],
+        }
+    }
+
+    fn get_javascript_pack(&self) -> Vec<PackageManager> {
+        let nvm_dir = std::env::var_os("NVM_DIR")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".nvm")))
+            .unwrap_or_else(|| PathBuf::from(".nvm"));
+        let nvm = PackageManager::new("nvm", "Node Version Manager", "POSIX shell script to manage multiple Node.js versions", "nvm")
+            .with_kind(ManagerKind::VersionManager);
+        let nvm = if cfg!(target_os = "windows") {
+            nvm
+        } else {
+            nvm.with_script(nvm_dir.join("nvm.sh"))
+        };
+
+        vec![
+            PackageManager::new("npm", "Node Package Manager", "Node.js package manager", "npm")
+                .with_cache_commands(&["config", "get", "cache"], &["cache", "clean", "--force"]),
+            PackageManager::new("yarn", "Yarn Package Manager", "Fast, reliable, and secure dependency management", "yarn")
+                .with_cache_commands(&["cache", "dir"], &["cache", "clean"]),
+            PackageManager::new("pnpm", "Performant npm", "Fast, disk space efficient package manager", "pnpm")
+                .with_cache_commands(&["store", "path"], &["store", "prune"]),
+            PackageManager::new("node", "Node.js", "JavaScript runtime that npm, yarn and pnpm run on", "node")
+                .with_kind(ManagerKind::Runtime),
+            PackageManager::new("deno", "Deno", "Secure JavaScript and TypeScript runtime", "deno")
+                .with_kind(ManagerKind::Runtime)
+                .with_version_pattern(r"^deno (\d+\.\d+\.\d+\S*)"),
+            // Bun is also a runtime, but it is grouped with the package
+            // managers so that kind-filtered views such as `list` keep it there.
+            PackageManager::new("bun", "Bun Runtime & Package Manager", "Incredibly fast JavaScript runtime and package manager", "bun")
+                .with_cache_commands(&["pm", "cache"], &["pm", "cache", "rm"]),
+            PackageManager::new("corepack", "Corepack", "Node.js tool that provides the package manager pinned by a project", "corepack")
+                .with_kind(ManagerKind::VersionManager),
+            nvm,
+            PackageManager::new("fnm", "Fast Node Manager", "Fast and simple Node.js version manager", "fnm")
+                .with_kind(ManagerKind::VersionManager)
+                .with_version_pattern(r"^fnm (\d+\.\d+\.\d+\S*)$"),
+            PackageManager::new("volta", "Volta", "Hassle-free JavaScript tool manager", "volta")
+                .with_kind(ManagerKind::VersionManager),
+            PackageManager::new("asdf", "asdf", "Multiple runtime version manager", "asdf")
+                .with_kind(ManagerKind::VersionManager)
+                .with_version_pattern(r"^(?:asdf version )?v?(\d+\.\d+\.\d+)"),
+            PackageManager::new("mise", "mise", "Polyglot tool version manager", "mise")
+                .with_kind(ManagerKind::VersionManager)
+                .with_version_pattern(r"^(?:mise )?v?(\d+\.\d+\.\d+)"),
+        ]
     }
 
     fn get_platform_command(&self, base_command: &str) -> String {
@@ -38,80 +183,132 @@ impl DefaultPackageManagerService {
         }
     }
 
-    async fn get_version(&self, command: &str) -> Result<String> {
-        let mut cmd = if cfg!(target_os = "windows") && !command.ends_with(".exe") {
+    fn get_version_command(&self, command: &str, args: &[String]) -> Command {
+        if cfg!(target_os = "windows") && !command.ends_with(".exe") {
             let mut c = Command::new("cmd");
-            c.args(["/C", command, "--version"]);
+            c.args(["/C", command]).args(args);
             c
         } else {
             let mut c = Command::new(command);
-            c.arg("--version");
+            c.args(args);
             c
-        };
+        }
+    }
+
+    /// Runs `command` with its version arguments after sourcing the script
+    /// that defines it.
+    fn get_script_version_command(&self, script: &Path, command: &str, args: &[String]) -> Command {
+        let mut c = Command::new("bash");
+        c.arg("-c")
+            .arg(r#". "$0" >/dev/null 2>&1 && "$@""#)
+            .arg(script)
+            .arg(command)
+            .args(args);
+        c
+    }
 
-        let output = cmd.output()?;
+    async fn get_version(&self, mut cmd: Command) -> Result<String> {
+        // A tool waiting for input (e.g. a Yarn download prompt) must not
+        // block the probe, and the child is killed if the timeout drops us.
+        cmd.stdin(Stdio::null()).kill_on_drop(true);
+        let output = cmd.output().await?;
 
         if output.status.success() {
             let version_output = String::from_utf8_lossy(&output.stdout);
-            let version = version_output.trim().lines().next().unwrap_or("unknown");
-            Ok(version.to_string())
+            Ok(version_output.trim().to_string())
         } else {
             let stderr_output = String::from_utf8_lossy(&output.stderr);
             if !stderr_output.trim().is_empty() {
-                let version = stderr_output.trim().lines().next().unwrap_or("unknown");
-                Ok(version.to_string())
+                Ok(stderr_output.trim().to_string())
             } else {
                 Err(anyhow::anyhow!("Failed to get version"))
             }
         }
     }
+
+    fn apply_version(&self, manager: PackageManager, output: &str) -> PackageManager {
+        match extract_version(output, manager.get_version_pattern()) {
+            Some((line, version)) => manager.with_version(line).with_parsed_version(version),
+            None => {
+                let first_line = output.lines().next().unwrap_or("unknown").to_string();
+                manager.with_version(first_line)
+            }
+        }
+    }
+
+    async fn probe(&self, manager: &PackageManager, path: PathBuf, cmd: Command) -> PackageManagerCheckResult {
+        let cache_key = self.cache.as_ref().and_then(|_| CacheKey::new(manager, &path));
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Some(mut cached) = cache.get(key) {
+                // Only the probe outcome is cached; the rest of the entry
+                // comes from the current definition.
+                let probed = cached.package_manager;
+                cached.package_manager = PackageManager {
+                    version: probed.version,
+                    parsed_version: probed.parsed_version,
+                    path: probed.path,
+                    is_installed: probed.is_installed,
+                    is_working: probed.is_working,
+                    ..manager.clone()
+                };
+                return cached;
+            }
+        }
+
+        let installed = manager.clone().with_path(path).mark_installed();
+
+        let result = match tokio::time::timeout(self.probe_timeout, self.get_version(cmd)).await {
+            Ok(Ok(output)) => {
+                PackageManagerCheckResult::success(self.apply_version(installed, &output).mark_working())
+            }
+            Ok(Err(e)) => PackageManagerCheckResult::failure(installed, e.to_string()),
+            Err(_) => PackageManagerCheckResult::timed_out(installed, self.probe_timeout),
+        };
+
+        // Failures and timeouts are retried on the next run rather than cached.
+        if let (Some(cache), Some(key)) = (&self.cache, cache_key) {
+            if result.success {
+                cache.put(key, &result);
+            }
+        }
+
+        result
+    }
 }
 
 #[async_trait::async_trait]
 impl PackageManagerService for DefaultPackageManagerService {
     fn get_supported_managers(&self) -> Vec<PackageManager> {
-        vec![
-            PackageManager::new("npm", "Node Package Manager", "Node.js package manager", "npm"),
-            PackageManager::new("yarn", "Yarn Package Manager", "Fast, reliable, and secure dependency management", "yarn"),
-            PackageManager::new("pnpm", "Performant npm", "Fast, disk space efficient package manager", "pnpm"),
-            PackageManager::new("bun", "Bun Runtime & Package Manager", "Incredibly fast JavaScript runtime and package manager", "bun"),
-        ]
-        .into_iter()
-        .map(|pm| {
-            let command = self.get_platform_command(&pm.command);
-            PackageManager {
-                command,
-                ..pm
+        let mut managers = self.get_builtin_managers();
+        for tool in &self.custom_tools {
+            match managers.iter_mut().find(|pm| pm.name == tool.name) {
+                Some(existing) => *existing = tool.clone(),
+                None => managers.push(tool.clone()),
             }
-        })
-        .collect()
+        }
+
+        managers
+            .into_iter()
+            .map(|pm| {
+                let command = self.get_platform_command(&pm.command);
+                PackageManager {
+                    command,
+                    ..pm
+                }
+            })
+            .collect()
     }
 
     async fn check_manager(&self, manager: &PackageManager) -> Result<PackageManagerCheckResult> {
-        let commands_to_try = self.get_commands_to_try(&manager.command);
-        
-        for cmd in &commands_to_try {
-            if let Ok(path) = which::which(cmd) {
-                match self.get_version(cmd).await {
-                    Ok(version) => {
-                        let mut updated_manager = manager.clone();
-                        updated_manager = updated_manager
-                            .with_version(version)
-                            .with_path(path)
-                            .mark_installed()
-                            .mark_working();
-                        return Ok(PackageManagerCheckResult::success(updated_manager));
-                    }
-                    Err(e) => {
-                        let mut updated_manager = manager.clone();
-                        updated_manager = updated_manager
-                            .with_path(path)
-                            .mark_installed();
-                        return Ok(PackageManagerCheckResult::failure(
-                            updated_manager,
-                            e.to_string(),
-                        ));
-                    }
+        if let Some(script) = manager.script.as_ref().filter(|_| !cfg!(target_os = "windows")) {
+            if script.is_file() {
+                let cmd = self.get_script_version_command(script, &manager.command, &manager.version_args);
+                return Ok(self.probe(manager, script.clone(), cmd).await);
+            }
+        } else {
+            for cmd in self.get_commands_to_try(&manager.command) {
+                if let Ok(path) = which::which(&cmd) {
+                    return Ok(self.probe(manager, path, self.get_version_command(&cmd, &manager.version_args)).await);
                 }
             }
         }
@@ -123,18 +320,150 @@ impl PackageManagerService for DefaultPackageManagerService {
     }
 
     async fn check_managers(&self, managers: Vec<PackageManager>) -> Vec<PackageManagerCheckResult> {
-        let mut results = Vec::new();
-        
-        for manager in managers {
-            match self.check_manager(&manager).await {
-                Ok(result) => results.push(result),
-                Err(e) => results.push(PackageManagerCheckResult::failure(
-                    manager,
-                    e.to_string(),
-                )),
+        let probes = managers.iter().map(|manager| self.check_manager(manager));
+        let outcomes = join_all(probes).await;
+
+        managers
+            .into_iter()
+            .zip(outcomes)
+            .map(|(manager, outcome)| match outcome {
+                Ok(result) => result,
+                Err(e) => PackageManagerCheckResult::failure(manager, e.to_string()),
+            })
+            .collect()
+    }
+
+    async fn find_installations(&self, manager: &PackageManager) -> Vec<Installation> {
+        let mut paths = Vec::new();
+        for cmd in self.get_commands_to_try(&manager.command) {
+            if let Ok(found) = which::which_all(&cmd) {
+                for path in found {
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
             }
         }
-        
-        results
+
+        let probes = paths.iter().map(|path| {
+            let cmd = self.get_version_command(&path.to_string_lossy(), &manager.version_args);
+            self.probe(manager, path.clone(), cmd)
+        });
+
+        join_all(probes)
+            .await
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| Installation::from_result(result, index == 0))
+            .collect()
+    }
+
+    async fn check_binary(&self, manager: &PackageManager, path: &Path) -> PackageManagerCheckResult {
+        // Repo-local releases such as `.yarn/releases/yarn-4.1.0.cjs` are
+        // plain scripts and have to be run through node.
+        let is_script = path
+            .extension()
+            .is_some_and(|ext| ext == "js" || ext == "cjs" || ext == "mjs");
+
+        let cmd = if is_script {
+            let mut c = Command::new("node");
+            c.arg(path).args(&manager.version_args);
+            c
+        } else {
+            self.get_version_command(&path.to_string_lossy(), &manager.version_args)
+        };
+
+        self.probe(manager, path.to_path_buf(), cmd).await
+    }
+
+    fn detect_corepack_shims(&self) -> Vec<String> {
+        ["yarn", "pnpm"]
+            .iter()
+            .filter(|name| {
+                self.get_commands_to_try(&self.get_platform_command(name))
+                    .iter()
+                    .filter_map(|cmd| which::which(cmd).ok())
+                    .filter_map(|path| std::fs::canonicalize(path).ok())
+                    .any(|target| target.to_string_lossy().contains("corepack"))
+            })
+            .map(|name| name.to_string())
+            .collect()
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_version_patterns() {
+        let managers = DefaultPackageManagerService::new().get_supported_managers();
+        let extract = |name: &str, output: &str| {
+            let pm = managers.iter().find(|pm| pm.name == name).unwrap();
+            extract_version(output, pm.get_version_pattern()).map(|(_, v)| v.to_string())
+        };
+
+        assert_eq!(extract("node", "v20.11.1").as_deref(), Some("20.11.1"));
+        assert_eq!(
+            extract("deno", "deno 1.40.2 (release, x86_64-unknown-linux-gnu)\nv8 12.1.285.6").as_deref(),
+            Some("1.40.2")
+        );
+        assert_eq!(extract("fnm", "fnm 1.35.1").as_deref(), Some("1.35.1"));
+        assert_eq!(extract("asdf", "v0.14.0-ccdd47d").as_deref(), Some("0.14.0"));
+        assert_eq!(extract("asdf", "asdf version 0.16.2").as_deref(), Some("0.16.2"));
+        assert_eq!(extract("mise", "2024.1.0 linux-x64 (2024-01-01)").as_deref(), Some("2024.1.0"));
+    }
+
+    #[test]
+    fn test_ecosystem_pack_version_patterns() {
+        let managers = DefaultPackageManagerService::new().get_supported_managers();
+        let extract = |name: &str, output: &str| {
+            let pm = managers.iter().find(|pm| pm.name == name).unwrap();
+            extract_version(output, pm.get_version_pattern()).map(|(_, v)| v.to_string())
+        };
+
+        assert_eq!(extract("cargo", "cargo 1.75.0 (1d8b05cdd 2023-11-20)").as_deref(), Some("1.75.0"));
+        assert_eq!(
+            extract("rustup", "rustup 1.26.0 (5af9b9484 2023-04-05)\ninfo: the currently active `rustc` version is `rustc 1.75.0`").as_deref(),
+            Some("1.26.0")
+        );
+        assert_eq!(extract("pip", "pip 24.0 from /usr/lib/python3/dist-packages/pip (python 3.12)").as_deref(), Some("24.0.0"));
+        assert_eq!(extract("poetry", "Poetry (version 1.7.1)").as_deref(), Some("1.7.1"));
+        assert_eq!(extract("python", "Python 3.11.7").as_deref(), Some("3.11.7"));
+        assert_eq!(extract("go", "go version go1.21.5 linux/amd64").as_deref(), Some("1.21.5"));
+        assert_eq!(extract("go", "go version go1.20 darwin/arm64").as_deref(), Some("1.20.0"));
+        assert_eq!(extract("composer", "Composer version 2.6.6 2023-12-08 18:32:26").as_deref(), Some("2.6.6"));
+        assert_eq!(extract("php", "PHP 8.2.12 (cli) (built: Oct 24 2023 21:15:15) (NTS)").as_deref(), Some("8.2.12"));
+
+        let go = managers.iter().find(|pm| pm.name == "go").unwrap();
+        assert_eq!(go.ecosystem, Ecosystem::Go);
+        assert_eq!(go.version_args, vec!["version"]);
+    }
+
+    #[test]
+    fn test_registry_kinds() {
+        let managers = DefaultPackageManagerService::new().get_supported_managers();
+        let kind = |name: &str| managers.iter().find(|pm| pm.name == name).unwrap().kind;
+
+        assert_eq!(kind("pnpm"), ManagerKind::PackageManager);
+        assert_eq!(kind("bun"), ManagerKind::PackageManager);
+        assert_eq!(kind("deno"), ManagerKind::Runtime);
+        assert_eq!(kind("volta"), ManagerKind::VersionManager);
+    }
+
+    #[test]
+    fn test_custom_tools_extend_and_replace_builtins() {
+        let service = DefaultPackageManagerService::new().with_custom_tools(vec![
+            PackageManager::new("turbo", "Turborepo", "", "turbo").with_kind(ManagerKind::Tool),
+            PackageManager::new("bun", "Bun", "", "bun")
+                .with_kind(ManagerKind::Runtime)
+                .with_version_args(vec!["-v".to_string()]),
+        ]);
+        let managers = service.get_supported_managers();
+
+        assert_eq!(managers.iter().filter(|pm| pm.name == "bun").count(), 1);
+        let bun = managers.iter().find(|pm| pm.name == "bun").unwrap();
+        assert_eq!(bun.version_args, vec!["-v"]);
+        assert_eq!(managers.last().unwrap().name, "turbo");
+    }
+}
@@ -0,0 +1,88 @@
+use crate::models::NativeCommand;
+use crate::utils::constants::EXIT_ERROR;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::ExitStatus;
+use tokio::process::Command;
+
+#[async_trait::async_trait]
+pub trait ProcessService {
+    /// Runs `command` attached to the terminal, in `directory` when given,
+    /// and returns its exit code.
+    async fn run(&self, command: &NativeCommand, directory: Option<&Path>) -> Result<u8>;
+}
+
+#[derive(Clone)]
+pub struct DefaultProcessService;
+
+impl DefaultProcessService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// npm, pnpm and yarn are `.cmd` shims on Windows, which only `cmd`
+    /// can resolve.
+    fn get_command(&self, command: &NativeCommand) -> Command {
+        if cfg!(target_os = "windows") {
+            let mut c = Command::new("cmd");
+            c.args(["/C", &command.program]).args(&command.args);
+            c
+        } else {
+            let mut c = Command::new(&command.program);
+            c.args(&command.args);
+            c
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessService for DefaultProcessService {
+    async fn run(&self, command: &NativeCommand, directory: Option<&Path>) -> Result<u8> {
+        let mut cmd = self.get_command(command);
+        if let Some(directory) = directory {
+            cmd.current_dir(directory);
+        }
+        let status = cmd
+            .status()
+            .await
+            .with_context(|| format!("Failed to run {}", command.program))?;
+        Ok(get_exit_code(status))
+    }
+}
+
+/// Like a shell, reports death by signal N as 128 + N.
+#[cfg(unix)]
+fn get_exit_code(status: ExitStatus) -> u8 {
+    use std::os::unix::process::ExitStatusExt;
+    match (status.code(), status.signal()) {
+        (Some(code), _) => clamp_exit_code(code),
+        (None, Some(signal)) => clamp_exit_code(128 + signal),
+        (None, None) => EXIT_ERROR,
+    }
+}
+
+#[cfg(not(unix))]
+fn get_exit_code(status: ExitStatus) -> u8 {
+    status.code().map(clamp_exit_code).unwrap_or(EXIT_ERROR)
+}
+
+/// A code that does not fit in a byte, such as 256 or a Windows NTSTATUS,
+/// must not wrap around to success or to an unrelated code.
+fn clamp_exit_code(code: i32) -> u8 {
+    u8::try_from(code).unwrap_or(EXIT_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_exit_code() {
+        assert_eq!(clamp_exit_code(0), 0);
+        assert_eq!(clamp_exit_code(2), 2);
+        assert_eq!(clamp_exit_code(255), 255);
+        assert_eq!(clamp_exit_code(256), EXIT_ERROR);
+        assert_eq!(clamp_exit_code(-1), EXIT_ERROR);
+        assert_eq!(clamp_exit_code(0xC000_0005_u32 as i32), EXIT_ERROR);
+    }
+}
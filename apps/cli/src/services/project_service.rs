@@ -1,21 +1,205 @@
-use crate::models::{ProjectConfig, ProjectGenerationResult, ProjectTemplate};
-use anyhow::Result;
+use crate::models::{
+    ProjectConfig, ProjectFile, ProjectGenerationResult, ProjectStructure, ProjectTemplate, TemplateManifest,
+    TemplateSource,
+};
+use crate::utils::constants::TEMPLATE_DIRECTORY;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Describes a template on disk; every other file in its directory is copied.
+const TEMPLATE_MANIFEST: &str = "template.json";
 
 pub trait ProjectService {
     fn create_project(&self, config: &ProjectConfig) -> Result<ProjectGenerationResult>;
     fn list_templates(&self) -> Vec<ProjectTemplate>;
+    /// Why configured templates or template directories could not be used.
+    fn get_template_warnings(&self) -> Vec<String>;
     fn get_template(&self, name: &str) -> Option<ProjectTemplate>;
     fn validate_project_name(&self, name: &str) -> Result<()>;
 }
 
 #[derive(Clone)]
-pub struct DefaultProjectService;
+pub struct DefaultProjectService {
+    configured_templates: Vec<(String, PathBuf)>,
+    template_directories: Vec<PathBuf>,
+    user_template_dir: Option<PathBuf>,
+    /// Templates and warnings, read from disk on first use.
+    loaded: OnceLock<(Vec<ProjectTemplate>, Vec<String>)>,
+}
 
 impl DefaultProjectService {
     pub fn new() -> Self {
-        Self
+        Self {
+            configured_templates: Vec::new(),
+            template_directories: Vec::new(),
+            user_template_dir: dirs::config_dir().map(|dir| dir.join("fazrepo").join("templates")),
+            loaded: OnceLock::new(),
+        }
+    }
+
+    /// The `templates` entries of `.fazrepo`, each a template directory.
+    pub fn with_configured_templates(mut self, templates: &HashMap<String, String>) -> Self {
+        let mut templates: Vec<(String, PathBuf)> = templates
+            .iter()
+            .map(|(name, path)| (name.clone(), expand_home(path)))
+            .collect();
+        templates.sort();
+        self.configured_templates = templates;
+        self
+    }
+
+    pub fn with_template_directories(mut self, directories: &[PathBuf]) -> Self {
+        self.template_directories = directories
+            .iter()
+            .map(|dir| expand_home(&dir.to_string_lossy()))
+            .collect();
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_user_template_dir(mut self, directory: Option<PathBuf>) -> Self {
+        self.user_template_dir = directory;
+        self
+    }
+
+    fn get_loaded(&self) -> &(Vec<ProjectTemplate>, Vec<String>) {
+        self.loaded.get_or_init(|| self.load_templates())
+    }
+
+    /// Every template, local ones first. A local template replaces the
+    /// built-in or lower-precedence one with the same name: `.fazrepo`
+    /// entries, then `template_directories` in order, then the user
+    /// template directory.
+    fn load_templates(&self) -> (Vec<ProjectTemplate>, Vec<String>) {
+        let mut candidates: Vec<(String, PathBuf, TemplateSource)> = Vec::new();
+        let mut warnings = Vec::new();
+        let built_ins = self.get_default_templates();
+
+        for (name, path) in &self.configured_templates {
+            if path.is_dir() {
+                candidates.push((name.clone(), path.clone(), TemplateSource::Config(path.clone())));
+            } else if built_ins.iter().any(|t| t.name == *name) && *path == Path::new(TEMPLATE_DIRECTORY).join(name) {
+                // Written by `fazrepo init`; the built-in is used until the directory exists.
+            } else {
+                warnings.push(format!("Template '{}' in .fazrepo points to {}, which is not a directory", name, path.display()));
+            }
+        }
+        for directory in &self.template_directories {
+            if !directory.is_dir() {
+                warnings.push(format!("Template directory {} does not exist", directory.display()));
+                continue;
+            }
+            for (name, path) in self.list_template_directories(directory) {
+                candidates.push((name, path.clone(), TemplateSource::Directory(path)));
+            }
+        }
+        if let Some(directory) = self.user_template_dir.as_ref().filter(|d| d.is_dir()) {
+            for (name, path) in self.list_template_directories(directory) {
+                candidates.push((name, path.clone(), TemplateSource::User(path)));
+            }
+        }
+
+        let mut templates: Vec<ProjectTemplate> = Vec::new();
+        let local = candidates.into_iter().filter_map(|(name, path, source)| {
+            match self.load_template(&name, &path, source) {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    warnings.push(format!("Skipped template '{}' from {}: {:#}", name, path.display(), e));
+                    None
+                }
+            }
+        });
+        for template in local.collect::<Vec<_>>().into_iter().chain(built_ins) {
+            match templates.iter_mut().find(|t| t.name == template.name) {
+                Some(existing) => {
+                    existing.overrides.get_or_insert(template.source);
+                }
+                None => templates.push(template),
+            }
+        }
+        (templates, warnings)
+    }
+
+    /// Non-hidden subdirectories, named after the directory.
+    fn list_template_directories(&self, directory: &Path) -> Vec<(String, PathBuf)> {
+        let mut found: Vec<(String, PathBuf)> = fs::read_dir(directory)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.is_dir())
+                    .filter_map(|path| {
+                        let name = path.file_name()?.to_string_lossy().to_string();
+                        (!name.starts_with('.')).then_some((name, path))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        found.sort();
+        found
+    }
+
+    fn load_template(&self, name: &str, directory: &Path, source: TemplateSource) -> Result<ProjectTemplate> {
+        let manifest_path = directory.join(TEMPLATE_MANIFEST);
+        let manifest: TemplateManifest = if manifest_path.is_file() {
+            let content = fs::read_to_string(&manifest_path)
+                .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+            serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", manifest_path.display()))?
+        } else {
+            TemplateManifest::default()
+        };
+
+        let mut structure = ProjectStructure {
+            directories: Vec::new(),
+            files: Vec::new(),
+        };
+        self.collect_template_files(directory, "", &mut structure)?;
+        if structure.files.is_empty() {
+            anyhow::bail!("it has no files");
+        }
+        Ok(manifest.into_template(name, source, structure))
+    }
+
+    fn collect_template_files(&self, root: &Path, relative: &str, structure: &mut ProjectStructure) -> Result<()> {
+        let directory = root.join(relative);
+        let mut entries: Vec<PathBuf> = fs::read_dir(&directory)
+            .with_context(|| format!("Failed to read {}", directory.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if name == ".git" || name == "node_modules" || (relative.is_empty() && name == TEMPLATE_MANIFEST) {
+                continue;
+            }
+            let child = if relative.is_empty() { name } else { format!("{}/{}", relative, name) };
+            if path.is_dir() {
+                structure.directories.push(child.clone());
+                self.collect_template_files(root, &child, structure)?;
+            } else {
+                let bytes = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+                // Placeholders are only replaced in text files.
+                let file = match String::from_utf8(bytes) {
+                    Ok(content) => ProjectFile {
+                        path: child,
+                        content,
+                        is_template: true,
+                        bytes: None,
+                    },
+                    Err(e) => ProjectFile {
+                        path: child,
+                        content: String::new(),
+                        is_template: false,
+                        bytes: Some(e.into_bytes()),
+                    },
+                };
+                structure.files.push(file);
+            }
+        }
+        Ok(())
     }
 
     fn get_default_templates(&self) -> Vec<ProjectTemplate> {
@@ -53,30 +237,37 @@ impl DefaultProjectService {
                             path: "package.json".to_string(),
                             content: include_str!("../../templates/fullstack-nextjs/package.json").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                         crate::models::ProjectFile {
                             path: "next.config.js".to_string(),
                             content: include_str!("../../templates/fullstack-nextjs/next.config.js").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                         crate::models::ProjectFile {
                             path: "tailwind.config.js".to_string(),
                             content: include_str!("../../templates/fullstack-nextjs/tailwind.config.js").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                         crate::models::ProjectFile {
                             path: "tsconfig.json".to_string(),
                             content: include_str!("../../templates/fullstack-nextjs/tsconfig.json").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                         crate::models::ProjectFile {
                             path: "README.md".to_string(),
                             content: include_str!("../../templates/fullstack-nextjs/README.md").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                     ],
                 },
                 config_files: vec![],
+                source: TemplateSource::BuiltIn,
+                overrides: None,
             },
             ProjectTemplate {
                 name: "api-express".to_string(),
@@ -108,20 +299,25 @@ impl DefaultProjectService {
                             path: "package.json".to_string(),
                             content: include_str!("../../templates/api-express/package.json").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                         crate::models::ProjectFile {
                             path: "tsconfig.json".to_string(),
                             content: include_str!("../../templates/api-express/tsconfig.json").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                         crate::models::ProjectFile {
                             path: "README.md".to_string(),
                             content: include_str!("../../templates/api-express/README.md").to_string(),
                             is_template: true,
+                            bytes: None,
                         },
                     ],
                 },
                 config_files: vec![],
+                source: TemplateSource::BuiltIn,
+                overrides: None,
             },
         ]
     }
@@ -191,10 +387,12 @@ impl ProjectService for DefaultProjectService {
                 }
             }
 
-            let content = if file.is_template {
-                self.process_template_content(&file.content, config)
+            let content = if let Some(bytes) = &file.bytes {
+                bytes.clone()
+            } else if file.is_template {
+                self.process_template_content(&file.content, config).into_bytes()
             } else {
-                file.content.clone()
+                file.content.clone().into_bytes()
             };
 
             if let Err(e) = fs::write(&file_path, content) {
@@ -225,13 +423,15 @@ impl ProjectService for DefaultProjectService {
     }
 
     fn list_templates(&self) -> Vec<ProjectTemplate> {
-        self.get_default_templates()
+        self.get_loaded().0.clone()
+    }
+
+    fn get_template_warnings(&self) -> Vec<String> {
+        self.get_loaded().1.clone()
     }
 
     fn get_template(&self, name: &str) -> Option<ProjectTemplate> {
-        self.get_default_templates()
-            .into_iter()
-            .find(|t| t.name == name)
+        self.get_loaded().0.iter().find(|t| t.name == name).cloned()
     }
 
     fn validate_project_name(&self, name: &str) -> Result<()> {
@@ -249,4 +449,76 @@ impl ProjectService for DefaultProjectService {
 
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+/// Expands a leading `~/` so `.fazrepo` paths can point into the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_templates_override_built_ins() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("user");
+        let team = dir.path().join("team");
+        for (template, file) in [
+            (user.join("api-express"), "user.txt"),
+            (user.join("cli-rust"), "Cargo.toml"),
+            (team.join("api-express"), "src/index.ts"),
+        ] {
+            fs::create_dir_all(template.join(file).parent().unwrap()).unwrap();
+            fs::write(template.join(file), "{{PROJECT_NAME}}").unwrap();
+        }
+        fs::write(team.join("api-express").join(TEMPLATE_MANIFEST), r#"{"description": "Team API", "category": "Backend"}"#).unwrap();
+        fs::create_dir_all(user.join("empty")).unwrap();
+        fs::write(user.join("cli-rust").join("logo.png"), [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+
+        let service = DefaultProjectService::new()
+            .with_template_directories(&[team.clone(), dir.path().join("missing")])
+            .with_user_template_dir(Some(user.clone()));
+
+        let templates = service.list_templates();
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["api-express", "cli-rust", "fullstack-nextjs"]);
+
+        let api = &templates[0];
+        assert_eq!(api.description, "Team API");
+        assert_eq!(api.source, TemplateSource::Directory(team.join("api-express")));
+        assert_eq!(api.overrides, Some(TemplateSource::User(user.join("api-express"))));
+        assert_eq!(api.structure.directories, vec!["src"]);
+        assert_eq!(api.structure.files[0].path, "src/index.ts");
+        let logo = &templates[1].structure.files[1];
+        assert_eq!(logo.bytes.as_deref(), Some(&[0x89, b'P', b'N', b'G', 0xff][..]));
+        assert!(!logo.is_template);
+        assert_eq!(templates[2].source, TemplateSource::BuiltIn);
+
+        let warnings = service.get_template_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("Template directory"));
+        assert!(warnings[1].starts_with("Skipped template 'empty'"));
+    }
+
+    #[test]
+    fn test_init_entries_fall_back_to_built_ins() {
+        // Whether or not `templates/<name>` exists in the working directory,
+        // the entries `fazrepo init` writes never warn.
+        let configured = HashMap::from([
+            ("fullstack-nextjs".to_string(), format!("{}/fullstack-nextjs", TEMPLATE_DIRECTORY)),
+            ("api-express".to_string(), format!("{}/api-express", TEMPLATE_DIRECTORY)),
+        ]);
+        let service = DefaultProjectService::new()
+            .with_configured_templates(&configured)
+            .with_user_template_dir(None);
+
+        assert!(service.get_template_warnings().is_empty());
+        assert!(service.get_template("fullstack-nextjs").is_some());
+        assert!(service.get_template("api-express").is_some());
+    }
+}
@@ -0,0 +1,674 @@
+use crate::models::{
+    expand_yarn_env, normalize_registry, normalize_scope, parse_version, redact_url, AuthStatus, Bunfig, ConfigSource,
+    ManagerRegistryConfig, Npmrc, RegistryReport, RegistrySource, ResolvedRegistry, ScopedRegistry,
+    SourcedValue, YarnClassicRc, YarnRc, DEFAULT_NPM_REGISTRY,
+};
+use crate::services::{DefaultYarnService, YarnService};
+use crate::utils::constants::DEFAULT_PROBE_TIMEOUT_MS;
+use anyhow::{Context, Result};
+use semver::Version;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[async_trait::async_trait]
+pub trait RegistryService {
+    /// Project, user and global `.npmrc` files for `directory`, highest
+    /// precedence first.
+    fn find_npmrc_files(&self, directory: &Path) -> Result<Vec<Npmrc>>;
+    fn resolve_registry(&self, directory: &Path) -> Result<ResolvedRegistry>;
+    /// The registry, scopes, auth and proxies each manager would use in `directory`.
+    fn inspect(&self, directory: &Path) -> Result<RegistryReport>;
+    async fn get_latest_version(&self, registry: &ResolvedRegistry, package: &str) -> Result<Version>;
+    /// HTTP status of the registry's `-/ping` endpoint, as used by `npm ping`.
+    async fn ping(&self, registry: &ResolvedRegistry) -> Result<u16>;
+}
+
+#[derive(Debug, Deserialize)]
+struct Packument {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: HashMap<String, String>,
+}
+
+/// Yarn's default registry, a mirror of registry.npmjs.org.
+const YARN_REGISTRY: &str = "https://registry.yarnpkg.com";
+
+#[derive(Clone)]
+pub struct DefaultRegistryService {
+    yarn_service: DefaultYarnService,
+    client: reqwest::Client,
+    registry: Option<(String, RegistrySource)>,
+    timeout: Duration,
+    /// Environment variables and home directory the config files are
+    /// resolved against, captured from the process by `new()`.
+    env: HashMap<String, String>,
+    home_dir: Option<PathBuf>,
+}
+
+impl DefaultRegistryService {
+    pub fn new() -> Self {
+        Self {
+            yarn_service: DefaultYarnService::new(),
+            client: reqwest::Client::new(),
+            registry: None,
+            timeout: Duration::from_millis(DEFAULT_PROBE_TIMEOUT_MS),
+            env: std::env::vars_os()
+                .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+                .collect(),
+            home_dir: dirs::home_dir(),
+        }
+    }
+
+    /// Uses `url` instead of whatever the environment and `.npmrc` say.
+    pub fn with_registry(mut self, url: &str, source: RegistrySource) -> Self {
+        self.registry = Some((url.to_string(), source));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_environment(mut self, home_dir: &Path, env: &[(&str, &str)]) -> Self {
+        self.home_dir = Some(home_dir.to_path_buf());
+        self.env = env
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        self
+    }
+
+    fn get_var(&self, name: &str) -> Option<&str> {
+        self.env.get(name).map(String::as_str)
+    }
+
+    fn get_user_npmrc_path(&self) -> Option<PathBuf> {
+        self.get_var("NPM_CONFIG_USERCONFIG")
+            .or_else(|| self.get_var("npm_config_userconfig"))
+            .map(PathBuf::from)
+            .or_else(|| self.home_dir.as_ref().map(|home| home.join(".npmrc")))
+    }
+
+    /// npm's global config lives under its prefix, which defaults to the
+    /// directory node is installed in.
+    fn get_global_npmrc_path(&self) -> Option<PathBuf> {
+        if let Some(path) = self
+            .get_var("NPM_CONFIG_GLOBALCONFIG")
+            .or_else(|| self.get_var("npm_config_globalconfig"))
+        {
+            return Some(PathBuf::from(path));
+        }
+
+        let node = which::which_in("node", Some(self.get_var("PATH")?), ".").ok()?;
+        let bin = node.parent()?;
+        if cfg!(target_os = "windows") {
+            Some(bin.join("etc").join("npmrc"))
+        } else {
+            Some(bin.parent()?.join("etc").join("npmrc"))
+        }
+    }
+
+    fn read_npmrc(&self, path: &Path) -> Result<Npmrc> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Npmrc::parse(path.to_path_buf(), &content, &self.env))
+    }
+
+    fn find_bunfig_files(&self, directory: &Path) -> Result<Vec<Bunfig>> {
+        let mut paths: Vec<PathBuf> = directory
+            .ancestors()
+            .map(|dir| dir.join("bunfig.toml"))
+            .find(|path| path.is_file())
+            .into_iter()
+            .collect();
+
+        let global = self
+            .get_var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| self.home_dir.clone())
+            .map(|dir| dir.join(".bunfig.toml"));
+        if let Some(global) = global.filter(|path| path.is_file()) {
+            paths.push(global);
+        }
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let mut bunfig: Bunfig = toml::from_str(&content)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                bunfig.path = path;
+                Ok(bunfig)
+            })
+            .collect()
+    }
+
+    fn get_env(&self, names: &[&str]) -> Option<SourcedValue> {
+        names.iter().find_map(|name| {
+            self.get_var(name)
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| SourcedValue::new(value, ConfigSource::Environment(name.to_string())))
+        })
+    }
+
+    fn get_npmrc_value(&self, npmrcs: &[Npmrc], key: &str) -> Option<SourcedValue> {
+        npmrcs.iter().find_map(|npmrc| {
+            npmrc
+                .get(key)
+                .map(|value| SourcedValue::new(value, ConfigSource::File(npmrc.path.clone())))
+        })
+    }
+
+    fn get_npmrc_auth(&self, npmrcs: &[Npmrc], registry: &str) -> AuthStatus {
+        npmrcs
+            .iter()
+            .find_map(|npmrc| {
+                npmrc
+                    .get_auth(registry)
+                    .map(|value| AuthStatus::from_value(Some(value), ConfigSource::File(npmrc.path.clone())))
+            })
+            .unwrap_or(AuthStatus::Missing)
+    }
+
+    /// Scopes from `.npmrc`, nearest file first.
+    fn get_npmrc_scopes(&self, npmrcs: &[Npmrc], scopes: &mut Vec<ScopedRegistry>) {
+        for npmrc in npmrcs {
+            for (scope, url) in npmrc.get_scopes() {
+                if !scopes.iter().any(|s| s.scope == scope) {
+                    scopes.push(ScopedRegistry {
+                        scope: scope.to_string(),
+                        registry: SourcedValue::new(url, ConfigSource::File(npmrc.path.clone())),
+                        auth: self.get_npmrc_auth(npmrcs, url),
+                    });
+                }
+            }
+        }
+    }
+
+    fn inspect_npm(&self, manager: &str, label: &str, npmrcs: &[Npmrc]) -> ManagerRegistryConfig {
+        let registry = self
+            .get_env(&["npm_config_registry", "NPM_CONFIG_REGISTRY"])
+            .or_else(|| self.get_npmrc_value(npmrcs, "registry"))
+            .unwrap_or_else(|| SourcedValue::new(DEFAULT_NPM_REGISTRY, ConfigSource::Default));
+
+        let mut scopes = Vec::new();
+        self.get_npmrc_scopes(npmrcs, &mut scopes);
+
+        ManagerRegistryConfig {
+            manager: manager.to_string(),
+            label: label.to_string(),
+            auth: self.get_npmrc_auth(npmrcs, &registry.value),
+            registry,
+            scopes,
+            http_proxy: self
+                .get_npmrc_value(npmrcs, "proxy")
+                .or_else(|| self.get_env(&["HTTP_PROXY", "http_proxy"])),
+            https_proxy: self
+                .get_npmrc_value(npmrcs, "https-proxy")
+                .or_else(|| self.get_env(&["HTTPS_PROXY", "https_proxy"])),
+            files: npmrcs.iter().map(|npmrc| npmrc.path.clone()).collect(),
+        }
+    }
+
+    /// Yarn Classic reads `.yarnrc` first and falls back to `.npmrc`.
+    fn inspect_yarn_classic(&self, yarnrcs: &[YarnClassicRc], npmrcs: &[Npmrc]) -> ManagerRegistryConfig {
+        let yarnrc_value = |key: &str| {
+            yarnrcs.iter().find_map(|yarnrc| {
+                yarnrc
+                    .get(key)
+                    .map(|value| SourcedValue::new(value, ConfigSource::File(yarnrc.path.clone())))
+            })
+        };
+
+        let registry = self
+            .get_env(&["npm_config_registry", "NPM_CONFIG_REGISTRY"])
+            .or_else(|| yarnrc_value("registry"))
+            .or_else(|| self.get_npmrc_value(npmrcs, "registry"))
+            .unwrap_or_else(|| SourcedValue::new(YARN_REGISTRY, ConfigSource::Default));
+
+        let mut scopes = Vec::new();
+        for yarnrc in yarnrcs {
+            for (key, url) in &yarnrc.entries {
+                let Some(scope) = key.strip_suffix(":registry").filter(|s| s.starts_with('@')) else {
+                    continue;
+                };
+                if !scopes.iter().any(|s: &ScopedRegistry| s.scope == scope) {
+                    scopes.push(ScopedRegistry {
+                        scope: scope.to_string(),
+                        registry: SourcedValue::new(url, ConfigSource::File(yarnrc.path.clone())),
+                        auth: self.get_npmrc_auth(npmrcs, url),
+                    });
+                }
+            }
+        }
+        self.get_npmrc_scopes(npmrcs, &mut scopes);
+
+        let mut files: Vec<PathBuf> = yarnrcs.iter().map(|yarnrc| yarnrc.path.clone()).collect();
+        files.extend(npmrcs.iter().map(|npmrc| npmrc.path.clone()));
+
+        ManagerRegistryConfig {
+            manager: "yarn".to_string(),
+            label: "Yarn Classic".to_string(),
+            auth: self.get_npmrc_auth(npmrcs, &registry.value),
+            registry,
+            scopes,
+            http_proxy: yarnrc_value("proxy")
+                .or_else(|| self.get_npmrc_value(npmrcs, "proxy"))
+                .or_else(|| self.get_env(&["HTTP_PROXY", "http_proxy"])),
+            https_proxy: yarnrc_value("https-proxy")
+                .or_else(|| self.get_npmrc_value(npmrcs, "https-proxy"))
+                .or_else(|| self.get_env(&["HTTPS_PROXY", "https_proxy"])),
+            files,
+        }
+    }
+
+    /// Yarn Berry only reads `.yarnrc.yml`; the nearest file wins per setting.
+    fn inspect_yarn_berry(&self, yarnrcs: &[YarnRc]) -> ManagerRegistryConfig {
+        let value = |get: fn(&YarnRc) -> Option<&String>| {
+            yarnrcs.iter().find_map(|yarnrc| {
+                get(yarnrc).map(|value| SourcedValue::new(value, ConfigSource::File(yarnrc.path.clone())))
+            })
+        };
+        let registry_auth = |url: &str| {
+            let nerf = crate::models::nerf_dart(url);
+            yarnrcs
+                .iter()
+                .find_map(|yarnrc| {
+                    yarnrc
+                        .npm_registries
+                        .iter()
+                        .find(|(key, _)| crate::models::nerf_dart(key) == nerf)
+                        .and_then(|(_, settings)| settings.get_auth())
+                        .map(|auth| AuthStatus::from_value(Some(&expand_yarn_env(auth)), ConfigSource::File(yarnrc.path.clone())))
+                })
+                .unwrap_or(AuthStatus::Missing)
+        };
+
+        let registry = self
+            .get_env(&["YARN_NPM_REGISTRY_SERVER"])
+            .or_else(|| value(|yarnrc| yarnrc.npm_registry_server.as_ref()))
+            .unwrap_or_else(|| SourcedValue::new(YARN_REGISTRY, ConfigSource::Default));
+
+        let auth = match yarnrcs.iter().find_map(|yarnrc| {
+            yarnrc
+                .npm_auth_token
+                .as_deref()
+                .or(yarnrc.npm_auth_ident.as_deref())
+                .map(|auth| AuthStatus::from_value(Some(&expand_yarn_env(auth)), ConfigSource::File(yarnrc.path.clone())))
+        }) {
+            Some(auth) => auth,
+            None => registry_auth(&registry.value),
+        };
+
+        let mut scopes: Vec<ScopedRegistry> = Vec::new();
+        for yarnrc in yarnrcs {
+            let mut names: Vec<_> = yarnrc.npm_scopes.keys().collect();
+            names.sort();
+            for name in names {
+                let scope = normalize_scope(name);
+                if scopes.iter().any(|s| s.scope == scope) {
+                    continue;
+                }
+                let settings = &yarnrc.npm_scopes[name];
+                let url = settings.npm_registry_server.as_deref().unwrap_or(&registry.value);
+                let scope_auth = match settings.get_auth() {
+                    Some(auth) => AuthStatus::from_value(Some(&expand_yarn_env(auth)), ConfigSource::File(yarnrc.path.clone())),
+                    None => registry_auth(url),
+                };
+                scopes.push(ScopedRegistry {
+                    scope,
+                    registry: SourcedValue::new(url, ConfigSource::File(yarnrc.path.clone())),
+                    auth: scope_auth,
+                });
+            }
+        }
+
+        ManagerRegistryConfig {
+            manager: "yarn".to_string(),
+            label: "Yarn Berry".to_string(),
+            registry,
+            auth,
+            scopes,
+            http_proxy: value(|yarnrc| yarnrc.http_proxy.as_ref()),
+            https_proxy: value(|yarnrc| yarnrc.https_proxy.as_ref()),
+            files: yarnrcs.iter().map(|yarnrc| yarnrc.path.clone()).collect(),
+        }
+    }
+
+    /// Bun prefers `bunfig.toml` and falls back to `.npmrc`.
+    fn inspect_bun(&self, bunfigs: &[Bunfig], npmrcs: &[Npmrc]) -> ManagerRegistryConfig {
+        let bunfig_registry = bunfigs.iter().find_map(|bunfig| {
+            let registry = bunfig.install.as_ref()?.registry.as_ref()?;
+            Some((registry, bunfig.path.clone()))
+        });
+
+        let (registry, auth) = if let Some(registry) = self.get_env(&["BUN_CONFIG_REGISTRY", "NPM_CONFIG_REGISTRY"]) {
+            let auth = self.get_npmrc_auth(npmrcs, &registry.value);
+            (registry, auth)
+        } else if let Some((registry, path)) = bunfig_registry {
+            let auth = if registry.has_auth() {
+                AuthStatus::Present(ConfigSource::File(path.clone()))
+            } else {
+                self.get_npmrc_auth(npmrcs, registry.get_url())
+            };
+            (SourcedValue::new(registry.get_url(), ConfigSource::File(path)), auth)
+        } else {
+            let registry = self
+                .get_npmrc_value(npmrcs, "registry")
+                .unwrap_or_else(|| SourcedValue::new(DEFAULT_NPM_REGISTRY, ConfigSource::Default));
+            let auth = self.get_npmrc_auth(npmrcs, &registry.value);
+            (registry, auth)
+        };
+
+        let mut scopes: Vec<ScopedRegistry> = Vec::new();
+        for bunfig in bunfigs {
+            let Some(install) = &bunfig.install else {
+                continue;
+            };
+            let mut names: Vec<_> = install.scopes.keys().collect();
+            names.sort();
+            for name in names {
+                let scope = normalize_scope(name);
+                if scopes.iter().any(|s| s.scope == scope) {
+                    continue;
+                }
+                let scoped = &install.scopes[name];
+                let auth = if scoped.has_auth() {
+                    AuthStatus::Present(ConfigSource::File(bunfig.path.clone()))
+                } else {
+                    self.get_npmrc_auth(npmrcs, scoped.get_url())
+                };
+                scopes.push(ScopedRegistry {
+                    scope,
+                    registry: SourcedValue::new(scoped.get_url(), ConfigSource::File(bunfig.path.clone())),
+                    auth,
+                });
+            }
+        }
+        self.get_npmrc_scopes(npmrcs, &mut scopes);
+
+        let mut files: Vec<PathBuf> = bunfigs.iter().map(|bunfig| bunfig.path.clone()).collect();
+        files.extend(npmrcs.iter().map(|npmrc| npmrc.path.clone()));
+
+        ManagerRegistryConfig {
+            manager: "bun".to_string(),
+            label: "Bun".to_string(),
+            registry,
+            auth,
+            scopes,
+            http_proxy: self.get_env(&["HTTP_PROXY", "http_proxy"]),
+            https_proxy: self.get_env(&["HTTPS_PROXY", "https_proxy"]),
+            files,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RegistryService for DefaultRegistryService {
+    fn find_npmrc_files(&self, directory: &Path) -> Result<Vec<Npmrc>> {
+        let mut paths: Vec<PathBuf> = directory
+            .ancestors()
+            .map(|dir| dir.join(".npmrc"))
+            .find(|path| path.is_file())
+            .into_iter()
+            .collect();
+
+        for path in [self.get_user_npmrc_path(), self.get_global_npmrc_path()]
+            .into_iter()
+            .flatten()
+            .filter(|path| path.is_file())
+        {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        paths.iter().map(|path| self.read_npmrc(path)).collect()
+    }
+
+    fn resolve_registry(&self, directory: &Path) -> Result<ResolvedRegistry> {
+        let npmrc_files = self.find_npmrc_files(directory)?;
+
+        let env_registry = self
+            .get_var("npm_config_registry")
+            .or_else(|| self.get_var("NPM_CONFIG_REGISTRY"))
+            .filter(|url| !url.trim().is_empty());
+
+        let mut registry = if let Some((url, source)) = &self.registry {
+            ResolvedRegistry::new(url, source.clone())
+        } else if let Some(url) = env_registry {
+            ResolvedRegistry::new(url, RegistrySource::Environment)
+        } else if let Some((url, npmrc)) = npmrc_files
+            .iter()
+            .find_map(|npmrc| npmrc.get("registry").map(|url| (url, npmrc)))
+        {
+            ResolvedRegistry::new(url, RegistrySource::Npmrc(npmrc.path.clone()))
+        } else {
+            ResolvedRegistry::new(DEFAULT_NPM_REGISTRY, RegistrySource::Default)
+        };
+
+        registry.auth_token = npmrc_files
+            .iter()
+            .find_map(|npmrc| npmrc.get_auth_token(&registry.url))
+            .map(str::to_string);
+
+        Ok(registry)
+    }
+
+    fn inspect(&self, directory: &Path) -> Result<RegistryReport> {
+        let npmrcs = self.find_npmrc_files(directory)?;
+        let yarnrcs = self.yarn_service.find_yarnrc_files(directory)?;
+        let bunfigs = self.find_bunfig_files(directory)?;
+
+        // A `.yarnrc.yml` means the project (or user) is on Yarn Berry,
+        // which ignores `.yarnrc` and `.npmrc` entirely.
+        let yarn = if yarnrcs.is_empty() {
+            let classic = self.yarn_service.find_classic_yarnrc_files(directory)?;
+            self.inspect_yarn_classic(&classic, &npmrcs)
+        } else {
+            self.inspect_yarn_berry(&yarnrcs)
+        };
+
+        Ok(RegistryReport {
+            directory: directory.to_path_buf(),
+            managers: vec![
+                self.inspect_npm("npm", "npm", &npmrcs),
+                self.inspect_npm("pnpm", "pnpm", &npmrcs),
+                yarn,
+                self.inspect_bun(&bunfigs, &npmrcs),
+            ],
+        })
+    }
+
+    async fn ping(&self, registry: &ResolvedRegistry) -> Result<u16> {
+        let url = format!("{}-/ping", registry.url);
+        let mut request = self.client.get(&url).timeout(self.timeout);
+        if let Some(token) = &registry.auth_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to query {}", redact_url(&url)))?;
+        Ok(response.status().as_u16())
+    }
+
+    async fn get_latest_version(&self, registry: &ResolvedRegistry, package: &str) -> Result<Version> {
+        let url = registry.get_package_url(package);
+        // The abbreviated document is much smaller and is all we need.
+        let mut request = self
+            .client
+            .get(&url)
+            .timeout(self.timeout)
+            .header(reqwest::header::ACCEPT, "application/vnd.npm.install-v1+json");
+        if let Some(token) = &registry.auth_token {
+            request = request.bearer_auth(token);
+        }
+
+        let packument: Packument = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to query {}", redact_url(&url)))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid package metadata from {}", redact_url(&url)))?;
+
+        let latest = packument
+            .dist_tags
+            .get("latest")
+            .with_context(|| format!("{} has no `latest` dist-tag in {}", package, redact_url(&normalize_registry(&registry.url))))?;
+        parse_version(latest).with_context(|| format!("Invalid latest version '{}' for {}", latest, package))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_project_npmrc_sets_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".npmrc"), "registry=http://mirror.example.com/npm\n").unwrap();
+        let sub = dir.path().join("packages").join("app");
+        fs::create_dir_all(&sub).unwrap();
+
+        let home = tempfile::tempdir().unwrap();
+        let service = DefaultRegistryService::new().with_environment(home.path(), &[]);
+
+        let registry = service.resolve_registry(&sub).unwrap();
+        assert_eq!(registry.url, "http://mirror.example.com/npm/");
+        assert_eq!(registry.source, RegistrySource::Npmrc(dir.path().join(".npmrc")));
+
+        let registry = service
+            .with_registry("http://localhost:4873", RegistrySource::Flag)
+            .resolve_registry(&sub)
+            .unwrap();
+        assert_eq!(registry.url, "http://localhost:4873/");
+    }
+
+    #[test]
+    fn test_user_npmrc_and_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        fs::write(
+            home.path().join(".npmrc"),
+            "registry=https://npm.example.com/\n//npm.example.com/:_authToken=${NPM_TOKEN}\n",
+        )
+        .unwrap();
+
+        let registry = DefaultRegistryService::new()
+            .with_environment(home.path(), &[("NPM_TOKEN", "secret")])
+            .resolve_registry(dir.path())
+            .unwrap();
+        assert_eq!(registry.url, "https://npm.example.com/");
+        assert_eq!(registry.source, RegistrySource::Npmrc(home.path().join(".npmrc")));
+        assert_eq!(registry.auth_token.as_deref(), Some("secret"));
+
+        let registry = DefaultRegistryService::new()
+            .with_environment(home.path(), &[("npm_config_registry", "http://localhost:4873")])
+            .resolve_registry(dir.path())
+            .unwrap();
+        assert_eq!(registry.url, "http://localhost:4873/");
+        assert_eq!(registry.source, RegistrySource::Environment);
+    }
+
+    #[test]
+    fn test_inspect_reads_every_manager_config() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(".npmrc"),
+            "registry=https://npm.example.com/\n//npm.example.com/:_authToken=secret\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(".yarnrc.yml"),
+            "npmRegistryServer: \"https://npm.example.com\"\nnpmScopes:\n  acme:\n    npmRegistryServer: \"https://acme.example.com\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        fs::write(dir.path().join("bunfig.toml"), "[install]\nregistry = \"https://registry.npmjs.org\"\n").unwrap();
+
+        let home = tempfile::tempdir().unwrap();
+        let report = DefaultRegistryService::new()
+            .with_environment(home.path(), &[])
+            .inspect(dir.path())
+            .unwrap();
+        let npm = &report.managers[0];
+        assert_eq!(npm.registry.value, "https://npm.example.com/");
+        assert!(matches!(npm.auth, AuthStatus::Present(_)));
+
+        let yarn = report.managers.iter().find(|m| m.manager == "yarn").unwrap();
+        assert_eq!(yarn.label, "Yarn Berry");
+        assert_eq!(yarn.scopes[0].scope, "@acme");
+
+        let bun = report.managers.iter().find(|m| m.manager == "bun").unwrap();
+        assert_eq!(bun.registry.source, ConfigSource::File(dir.path().join("bunfig.toml")));
+        assert!(!report.is_registry_consistent("bun"));
+        assert!(report.is_registry_consistent("yarn"));
+    }
+
+    #[test]
+    fn test_bun_reads_global_bunfig_and_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_home = tempfile::tempdir().unwrap();
+        fs::write(
+            config_home.path().join(".bunfig.toml"),
+            "[install]\nregistry = \"https://bun.example.com\"\n",
+        )
+        .unwrap();
+        let bun = |env: &[(&str, &str)]| {
+            let report = DefaultRegistryService::new()
+                .with_environment(dir.path(), env)
+                .inspect(dir.path())
+                .unwrap();
+            report.managers.into_iter().find(|m| m.manager == "bun").unwrap()
+        };
+
+        let xdg = config_home.path().to_str().unwrap();
+        let global = bun(&[("XDG_CONFIG_HOME", xdg)]);
+        assert_eq!(global.registry.value, "https://bun.example.com");
+        assert_eq!(global.registry.source, ConfigSource::File(config_home.path().join(".bunfig.toml")));
+
+        let env = bun(&[("XDG_CONFIG_HOME", xdg), ("BUN_CONFIG_REGISTRY", "http://localhost:4873/")]);
+        assert_eq!(env.registry.value, "http://localhost:4873/");
+        assert_eq!(env.registry.source, ConfigSource::Environment("BUN_CONFIG_REGISTRY".to_string()));
+
+        assert_eq!(bun(&[]).registry.source, ConfigSource::Default);
+    }
+
+    #[tokio::test]
+    async fn test_latest_version_from_local_registry() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let read = socket.read(&mut request).await.unwrap();
+            assert!(String::from_utf8_lossy(&request[..read]).starts_with("GET /pnpm "));
+
+            let body = r#"{"name":"pnpm","dist-tags":{"latest":"9.1.0","next-9":"9.2.0-0"}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let registry = ResolvedRegistry::new(&format!("http://{}", address), RegistrySource::Flag);
+        let latest = DefaultRegistryService::new()
+            .get_latest_version(&registry, "pnpm")
+            .await
+            .unwrap();
+        assert_eq!(latest, Version::new(9, 1, 0));
+    }
+}
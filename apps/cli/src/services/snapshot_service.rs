@@ -0,0 +1,59 @@
+use crate::models::{EnvironmentSnapshot, PackageManagerCheckResult, SNAPSHOT_FORMAT_VERSION};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub trait SnapshotService {
+    /// Records `results` together with the OS, architecture and PATH of this machine.
+    fn capture(&self, results: Vec<PackageManagerCheckResult>) -> EnvironmentSnapshot;
+    fn load(&self, path: &Path) -> Result<EnvironmentSnapshot>;
+}
+
+#[derive(Clone)]
+pub struct DefaultSnapshotService;
+
+impl DefaultSnapshotService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn get_hostname(&self) -> Option<String> {
+        std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .ok()
+            .or_else(|| fs::read_to_string("/etc/hostname").ok())
+            .map(|hostname| hostname.trim().to_string())
+            .filter(|hostname| !hostname.is_empty())
+    }
+}
+
+impl SnapshotService for DefaultSnapshotService {
+    fn capture(&self, results: Vec<PackageManagerCheckResult>) -> EnvironmentSnapshot {
+        let path = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect())
+            .unwrap_or_default();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        EnvironmentSnapshot::new(results, path, now).with_hostname(self.get_hostname())
+    }
+
+    fn load(&self, path: &Path) -> Result<EnvironmentSnapshot> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let snapshot: EnvironmentSnapshot = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a fazrepo snapshot", path.display()))?;
+
+        if snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+            anyhow::bail!(
+                "{} was written by fazrepo {} in a newer snapshot format; upgrade fazrepo to compare it",
+                path.display(),
+                snapshot.fazrepo_version
+            );
+        }
+        Ok(snapshot)
+    }
+}
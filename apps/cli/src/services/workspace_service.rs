@@ -0,0 +1,273 @@
+use crate::models::{
+    matches_workspace_pattern, DependencyKind, PackageDependency, PackageJson, Workspace, WorkspacePackage,
+    WorkspaceSource,
+};
+use crate::services::{DefaultManifestService, ManifestService};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Task runner configs recognized at the workspace root.
+const TASK_RUNNERS: &[(&str, &str)] = &[("turbo.json", "turbo"), ("nx.json", "nx")];
+
+pub trait WorkspaceService {
+    /// Finds the workspace `directory` belongs to, nearest ancestor first,
+    /// and lists its packages. `None` when it is not in a workspace.
+    fn discover(&self, directory: &Path) -> Result<Option<Workspace>>;
+}
+
+#[derive(Clone)]
+pub struct DefaultWorkspaceService {
+    manifest_service: DefaultManifestService,
+}
+
+impl DefaultWorkspaceService {
+    pub fn new() -> Self {
+        Self {
+            manifest_service: DefaultManifestService::new(),
+        }
+    }
+
+    fn read_pnpm_workspace(&self, directory: &Path) -> Result<Option<Vec<String>>> {
+        let path = directory.join("pnpm-workspace.yaml");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(
+            yaml.get("packages")
+                .and_then(|p| p.as_sequence())
+                .map(|p| p.iter().filter_map(|p| p.as_str().map(str::to_string)).collect())
+                .unwrap_or_default(),
+        ))
+    }
+
+    /// Nx puts projects in `appsDir` and `libsDir`, `apps` and `libs` by default.
+    fn read_nx_layout(&self, directory: &Path) -> Result<Option<Vec<String>>> {
+        let path = directory.join("nx.json");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let nx: serde_json::Value =
+            serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+        let layout = nx.get("workspaceLayout");
+        let dir = |key: &str, default: &str| {
+            layout
+                .and_then(|l| l.get(key))
+                .and_then(|d| d.as_str())
+                .unwrap_or(default)
+                .trim_end_matches('/')
+                .to_string()
+        };
+        Ok(Some(vec![format!("{}/*", dir("appsDir", "apps")), format!("{}/*", dir("libsDir", "libs"))]))
+    }
+
+    fn read_root_manifest(&self, directory: &Path) -> Result<Option<PackageJson>> {
+        let path = directory.join("package.json");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        self.manifest_service.read_package_json(&path).map(Some)
+    }
+
+    /// pnpm only reads pnpm-workspace.yaml, so it wins over package.json.
+    fn find_root(&self, directory: &Path) -> Result<Option<Workspace>> {
+        for dir in directory.ancestors() {
+            let manifest = self.read_root_manifest(dir)?;
+            let declared = manifest.as_ref().and_then(|m| m.get_workspace_patterns()).map(<[String]>::to_vec);
+
+            let workspace = if let Some(patterns) = self.read_pnpm_workspace(dir)? {
+                let mut workspace = Workspace::new(dir.to_path_buf(), WorkspaceSource::PnpmWorkspace, patterns);
+                if declared.as_ref().is_some_and(|d| *d != workspace.patterns) {
+                    workspace.warnings.push(
+                        "package.json workspaces differ from pnpm-workspace.yaml, which is the one pnpm reads".to_string(),
+                    );
+                }
+                workspace
+            } else if let Some(patterns) = declared {
+                Workspace::new(dir.to_path_buf(), WorkspaceSource::PackageJson, patterns)
+            } else if let Some(patterns) = self.read_nx_layout(dir)? {
+                Workspace::new(dir.to_path_buf(), WorkspaceSource::NxLayout, patterns)
+            } else {
+                continue;
+            };
+
+            return Ok(Some(Workspace {
+                name: manifest.and_then(|m| m.name),
+                ..workspace
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Directories matching `pattern`, walked from its literal prefix so
+    /// only the part of the tree the pattern can reach is read.
+    fn expand_pattern(&self, root: &Path, pattern: &str) -> Vec<String> {
+        let segments: Vec<&str> = pattern
+            .trim_start_matches("./")
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        let literal = segments.iter().take_while(|s| !s.contains(['*', '?'])).count();
+        let depth = if segments[literal..].contains(&"**") {
+            None
+        } else {
+            Some(segments.len() - literal)
+        };
+
+        let mut found = Vec::new();
+        self.walk(root, segments[..literal].join("/"), depth, pattern, &mut found);
+        found
+    }
+
+    fn walk(&self, root: &Path, relative: String, depth: Option<usize>, pattern: &str, found: &mut Vec<String>) {
+        if !relative.is_empty() && matches_workspace_pattern(pattern, &relative) {
+            found.push(relative.clone());
+        }
+        if depth == Some(0) {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(root.join(&relative)) else {
+            return;
+        };
+        let mut children: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name != "node_modules" && !name.starts_with('.'))
+            .collect();
+        children.sort();
+        for child in children {
+            let child = if relative.is_empty() { child } else { format!("{}/{}", relative, child) };
+            self.walk(root, child, depth.map(|d| d - 1), pattern, found);
+        }
+    }
+
+    fn find_package_directories(&self, workspace: &Workspace) -> Vec<String> {
+        let (excluded, included): (Vec<&String>, Vec<&String>) =
+            workspace.patterns.iter().partition(|p| p.starts_with('!'));
+        let accepts_project_json = workspace.task_runners.iter().any(|r| r == "nx");
+
+        let mut directories: Vec<String> = included
+            .iter()
+            .flat_map(|pattern| self.expand_pattern(&workspace.root, pattern))
+            .filter(|dir| !excluded.iter().any(|p| matches_workspace_pattern(&p[1..], dir)))
+            .filter(|dir| {
+                let dir = workspace.root.join(dir);
+                dir.join("package.json").is_file() || (accepts_project_json && dir.join("project.json").is_file())
+            })
+            .collect();
+        directories.sort();
+        directories.dedup();
+        directories
+    }
+
+    fn read_package(&self, root: &Path, relative: &str) -> Result<(WorkspacePackage, Vec<PackageDependency>)> {
+        let directory = root.join(relative);
+        let manifest_path = directory.join("package.json");
+        let manifest = if manifest_path.is_file() {
+            self.manifest_service.read_package_json(&manifest_path)?
+        } else {
+            // An Nx project without a package.json is named by project.json.
+            let path = directory.join("project.json");
+            let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let project: serde_json::Value =
+                serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+            PackageJson {
+                name: project.get("name").and_then(|n| n.as_str()).map(str::to_string),
+                ..PackageJson::default()
+            }
+        };
+
+        let dependencies = DependencyKind::ALL
+            .iter()
+            .flat_map(|kind| {
+                let declared = match kind {
+                    DependencyKind::Dependencies => &manifest.dependencies,
+                    DependencyKind::DevDependencies => &manifest.dev_dependencies,
+                    DependencyKind::PeerDependencies => &manifest.peer_dependencies,
+                    DependencyKind::OptionalDependencies => &manifest.optional_dependencies,
+                };
+                declared.iter().map(|(name, range)| PackageDependency {
+                    name: name.clone(),
+                    range: range.clone(),
+                    kind: *kind,
+                })
+            })
+            .collect();
+
+        let package = WorkspacePackage {
+            name: manifest.name,
+            path: PathBuf::from(relative),
+            version: manifest.version,
+            private: manifest.private,
+            internal_dependencies: Vec::new(),
+        };
+        Ok((package, dependencies))
+    }
+}
+
+impl WorkspaceService for DefaultWorkspaceService {
+    fn discover(&self, directory: &Path) -> Result<Option<Workspace>> {
+        let Some(mut workspace) = self.find_root(directory)? else {
+            return Ok(None);
+        };
+        workspace.task_runners = TASK_RUNNERS
+            .iter()
+            .filter(|(file, _)| workspace.root.join(file).is_file())
+            .map(|(_, runner)| runner.to_string())
+            .collect();
+
+        let mut packages = Vec::new();
+        for relative in self.find_package_directories(&workspace) {
+            match self.read_package(&workspace.root, &relative) {
+                Ok(package) => packages.push(package),
+                Err(e) => workspace.warnings.push(format!("Skipped {}: {:#}", relative, e)),
+            }
+        }
+        workspace.add_packages(packages);
+        Ok(Some(workspace))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_pnpm_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("package.json"), r#"{"name": "repo", "private": true}"#).unwrap();
+        fs::write(root.join("pnpm-workspace.yaml"), "packages:\n  - 'apps/*'\n  - 'packages/**'\n  - '!packages/legacy'\n").unwrap();
+        fs::write(root.join("turbo.json"), "{}").unwrap();
+        for (path, manifest) in [
+            ("apps/web", r#"{"name": "web", "private": true, "dependencies": {"ui": "workspace:*", "react": "^18.0.0"}}"#),
+            ("packages/ui", r#"{"name": "ui", "version": "1.0.0"}"#),
+            ("packages/nested/tokens", r#"{"name": "tokens", "version": "0.1.0"}"#),
+            ("packages/legacy", r#"{"name": "legacy"}"#),
+            ("packages/ui/node_modules/dep", r#"{"name": "dep"}"#),
+        ] {
+            fs::create_dir_all(root.join(path)).unwrap();
+            fs::write(root.join(path).join("package.json"), manifest).unwrap();
+        }
+        fs::create_dir_all(root.join("apps/empty")).unwrap();
+
+        let workspace = DefaultWorkspaceService::new().discover(&root.join("apps/web")).unwrap().unwrap();
+        assert_eq!(workspace.root, root);
+        assert_eq!(workspace.name.as_deref(), Some("repo"));
+        assert_eq!(workspace.source, WorkspaceSource::PnpmWorkspace);
+        assert_eq!(workspace.task_runners, vec!["turbo"]);
+
+        let paths: Vec<String> = workspace.packages.iter().map(|p| p.path.display().to_string()).collect();
+        assert_eq!(paths, vec!["apps/web", "packages/nested/tokens", "packages/ui"]);
+        let web = workspace.find_package("web").unwrap();
+        assert!(web.private);
+        assert_eq!(web.internal_dependencies.len(), 1);
+        assert_eq!(web.internal_dependencies[0].name, "ui");
+        assert!(workspace.warnings.is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::models::{YarnClassicRc, YarnRc};
+use crate::utils::constants::LOCKFILES;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub trait YarnService {
+    /// The nearest `.yarnrc.yml` between `start` and its project root. The
+    /// one in the home directory is user configuration, not the project's.
+    fn find_yarnrc(&self, start: &Path) -> Result<Option<YarnRc>>;
+    /// Every `.yarnrc.yml` Yarn Berry merges for `start`, nearest first,
+    /// with the one in the home directory last as a user-level override.
+    fn find_yarnrc_files(&self, start: &Path) -> Result<Vec<YarnRc>>;
+    /// The project and home `.yarnrc` files Yarn Classic reads, nearest first.
+    fn find_classic_yarnrc_files(&self, start: &Path) -> Result<Vec<YarnClassicRc>>;
+    fn list_releases(&self, project_dir: &Path) -> Vec<PathBuf>;
+}
+
+#[derive(Clone)]
+pub struct DefaultYarnService;
+
+impl DefaultYarnService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn read_yarnrc(&self, path: &Path) -> Result<YarnRc> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        // An empty .yarnrc.yml is valid and parses as YAML null.
+        let mut yarnrc: YarnRc = serde_yaml::from_str::<Option<YarnRc>>(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?
+            .unwrap_or_default();
+        yarnrc.path = path.to_path_buf();
+        Ok(yarnrc)
+    }
+
+    /// `start` and its ancestors up to the project root: the nearest
+    /// directory with a lockfile, else the nearest with a package.json. Empty
+    /// outside a project. The home directory is never a project root.
+    fn get_project_directories<'a>(&self, start: &'a Path) -> Vec<&'a Path> {
+        let home = dirs::home_dir();
+        let directories: Vec<&Path> = start
+            .ancestors()
+            .take_while(|dir| home.as_deref() != Some(*dir))
+            .collect();
+        let root = directories
+            .iter()
+            .position(|dir| LOCKFILES.iter().any(|(file, _)| dir.join(file).is_file()))
+            .or_else(|| directories.iter().position(|dir| dir.join("package.json").is_file()));
+        match root {
+            Some(root) => directories[..=root].to_vec(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Existing `file_name` files from `start` up to the project root, then
+    /// in the home directory.
+    fn find_files(&self, start: &Path, file_name: &str, nearest_only: bool) -> Vec<PathBuf> {
+        let found = self
+            .get_project_directories(start)
+            .into_iter()
+            .map(|dir| dir.join(file_name))
+            .filter(|candidate| candidate.is_file());
+        let mut paths: Vec<PathBuf> = if nearest_only {
+            found.take(1).collect()
+        } else {
+            found.collect()
+        };
+
+        if let Some(home) = dirs::home_dir().map(|home| home.join(file_name)) {
+            if home.is_file() && !paths.contains(&home) {
+                paths.push(home);
+            }
+        }
+        paths
+    }
+}
+
+impl YarnService for DefaultYarnService {
+    fn find_yarnrc(&self, start: &Path) -> Result<Option<YarnRc>> {
+        let Some(path) = self
+            .get_project_directories(start)
+            .into_iter()
+            .map(|dir| dir.join(".yarnrc.yml"))
+            .find(|candidate| candidate.is_file())
+        else {
+            return Ok(None);
+        };
+
+        self.read_yarnrc(&path).map(Some)
+    }
+
+    fn find_yarnrc_files(&self, start: &Path) -> Result<Vec<YarnRc>> {
+        self.find_files(start, ".yarnrc.yml", false)
+            .iter()
+            .map(|path| self.read_yarnrc(path))
+            .collect()
+    }
+
+    fn find_classic_yarnrc_files(&self, start: &Path) -> Result<Vec<YarnClassicRc>> {
+        self.find_files(start, ".yarnrc", true)
+            .into_iter()
+            .map(|path| {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                Ok(YarnClassicRc::parse(path, &content))
+            })
+            .collect()
+    }
+
+    fn list_releases(&self, project_dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(project_dir.join(".yarn").join("releases")) else {
+            return Vec::new();
+        };
+
+        let mut releases: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        releases.sort();
+        releases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_yarnrc_resolves_yarn_path() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("yarn.lock"), "").unwrap();
+        fs::write(
+            dir.path().join(".yarnrc.yml"),
+            "nodeLinker: node-modules\nyarnPath: .yarn/releases/yarn-4.1.0.cjs\n",
+        )
+        .unwrap();
+        let nested = dir.path().join("packages").join("app");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("package.json"), r#"{"name": "app"}"#).unwrap();
+
+        let yarnrc = DefaultYarnService::new().find_yarnrc(&nested).unwrap().unwrap();
+        assert_eq!(yarnrc.node_linker.as_deref(), Some("node-modules"));
+        assert_eq!(
+            yarnrc.get_yarn_path().unwrap(),
+            dir.path().join(".yarn/releases/yarn-4.1.0.cjs")
+        );
+    }
+
+    #[test]
+    fn test_empty_yarnrc_is_accepted() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".yarnrc.yml"), "").unwrap();
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        let yarnrc = DefaultYarnService::new().find_yarnrc(dir.path()).unwrap().unwrap();
+        assert!(yarnrc.yarn_path.is_none());
+    }
+
+    #[test]
+    fn test_find_yarnrc_stops_at_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".yarnrc.yml"), "yarnPath: .yarn/releases/yarn-4.1.0.cjs\n").unwrap();
+        let project = dir.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("package.json"), r#"{"name": "project"}"#).unwrap();
+
+        let service = DefaultYarnService::new();
+        assert!(service.find_yarnrc(&project).unwrap().is_none());
+        assert!(service.find_yarnrc(dir.path()).unwrap().is_none());
+    }
+}
@@ -4,5 +4,31 @@ pub const APP_DESCRIPTION: &str = "A CLI tool for full-stack project generation
 
 pub const DEFAULT_TEMPLATE: &str = "fullstack-nextjs";
 pub const CONFIG_FILE: &str = ".fazrepo";
+/// Where `fazrepo init` points the built-in templates, relative to the project.
+pub const TEMPLATE_DIRECTORY: &str = "templates";
 
-pub const SUPPORTED_PACKAGE_MANAGERS: &[&str] = &["npm", "yarn", "pnpm", "bun"]; 
\ No newline at end of file
+/// How long a single `--version` probe may run before it is reported as timed out.
+pub const DEFAULT_PROBE_TIMEOUT_MS: u64 = 10_000;
+
+/// How long a cached check result stays valid. A changed binary (new mtime)
+/// invalidates its entry earlier.
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 86_400;
+
+/// Oldest Node.js major line that still receives security updates.
+pub const MIN_MAINTAINED_NODE_MAJOR: u64 = 22;
+
+/// Lockfiles and the manager that writes them, in detection precedence order.
+pub const LOCKFILES: &[(&str, &str)] = &[
+    ("package-lock.json", "npm"),
+    ("npm-shrinkwrap.json", "npm"),
+    ("yarn.lock", "yarn"),
+    ("pnpm-lock.yaml", "pnpm"),
+    ("bun.lockb", "bun"),
+    ("bun.lock", "bun"),
+];
+
+/// Process exit codes, documented in the README under `fazrepo check`.
+pub const EXIT_SUCCESS: u8 = 0;
+pub const EXIT_ERROR: u8 = 1;
+pub const EXIT_REQUIREMENT_UNSATISFIED: u8 = 2;
+pub const EXIT_REQUIREMENT_MISSING: u8 = 3;
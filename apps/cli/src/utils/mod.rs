@@ -1,2 +1,4 @@
 pub mod constants;
-pub mod helpers; 
\ No newline at end of file
+// Baseline helpers kept for API compatibility; the commands do not call them yet.
+#[allow(dead_code)]
+pub mod helpers;
@@ -3,20 +3,20 @@ use std::process::Command;
 #[test]
 fn test_cli_help() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "--help"])
+        .args(["run", "--", "--help"])
         .current_dir(env!("CARGO_MANIFEST_DIR"))
         .output()
         .expect("Failed to execute command");
 
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("A CLI tool to check package manager versions"));
+    assert!(stdout.contains("A CLI tool for full-stack project generation and package manager checking"));
 }
 
 #[test]
 fn test_cli_version() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "--version"])
+        .args(["run", "--", "--version"])
         .current_dir(env!("CARGO_MANIFEST_DIR"))
         .output()
         .expect("Failed to execute command");
@@ -29,7 +29,7 @@ fn test_cli_version() {
 #[test]
 fn test_version_command() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "version"])
+        .args(["run", "--", "version"])
         .current_dir(env!("CARGO_MANIFEST_DIR"))
         .output()
         .expect("Failed to execute command");
@@ -42,7 +42,7 @@ fn test_version_command() {
 #[test]
 fn test_list_command() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "list"])
+        .args(["run", "--", "list"])
         .current_dir(env!("CARGO_MANIFEST_DIR"))
         .output()
         .expect("Failed to execute command");
@@ -55,3 +55,16 @@ fn test_list_command() {
     assert!(stdout.contains("pnpm"));
     assert!(stdout.contains("bun"));
 }
+
+#[test]
+fn test_check_rejects_unknown_requirement() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--require", "not-a-manager>=1"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Unknown package manager 'not-a-manager'"));
+}